@@ -0,0 +1,99 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+
+use crate::{block::Block, config::Config, solver::ScheduleSlot};
+
+/// The shifts assigned to a single human, answering the question "when am I on-call?".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Agenda {
+    pub human: String,
+    /// Each block of consecutive slots assigned to this human, in chronological order.
+    pub shifts: Vec<Block>,
+    /// The total on-call time across all of this human's shifts.
+    pub total: Duration,
+    /// The first shift which had not finished at the time the agenda was built (which may already be underway).
+    pub next: Option<Block>,
+    /// The longest period between the end of one of this human's shifts and the start of their following shift.
+    pub longest_gap: Option<Duration>,
+}
+
+impl Agenda {
+    /// Builds an agenda for every human in the configuration (including those without any shifts), with their
+    /// next shift being the first which has not finished by `now`.
+    pub fn for_humans(config: &Config, schedule: &[ScheduleSlot], now: NaiveDateTime) -> Vec<Agenda> {
+        let mut schedule = schedule.to_vec();
+        schedule.sort_by_key(|slot| slot.time.start);
+        let blocks = Block::merge(&schedule);
+
+        config.humans.keys().map(|human| {
+            let shifts: Vec<Block> = blocks.iter().filter(|block| block.human.as_deref() == Some(human.as_str())).cloned().collect();
+
+            Agenda {
+                human: human.clone(),
+                total: shifts.iter().fold(Duration::zero(), |total, shift| total + shift.duration),
+                next: shifts.iter().find(|shift| shift.time.end > now).cloned(),
+                longest_gap: shifts.windows(2).map(|pair| pair[1].time.start - pair[0].time.end).max(),
+                shifts,
+            }
+        }).collect()
+    }
+}
+
+impl Serialize for Agenda {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let hours = |d: Duration| d.num_seconds() as f64 / 3600.0;
+
+        let mut state = serializer.serialize_struct("Agenda", 5)?;
+        state.serialize_field("human", &self.human)?;
+        state.serialize_field("shifts", &self.shifts)?;
+        state.serialize_field("totalHours", &hours(self.total))?;
+        state.serialize_field("next", &self.next)?;
+        state.serialize_field("longestGapHours", &self.longest_gap.map(hours))?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{config::Human, timerange::TimeRange};
+
+    use super::*;
+
+    #[test]
+    fn test_agenda() {
+        let slot = |day: u32, human: &str| {
+            let start = NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_hms_opt(0, 0, 0).unwrap();
+            ScheduleSlot { time: TimeRange::new(start, start + Duration::days(1)), human: Some(human.to_string()) }
+        };
+
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default())
+            .with_human("claire@example.com", Human::default());
+
+        let schedule = vec![
+            slot(9, "alice@example.com"),
+            slot(2, "alice@example.com"),
+            slot(3, "alice@example.com"),
+            slot(4, "bob@example.com"),
+            slot(5, "alice@example.com"),
+            slot(6, "bob@example.com"),
+        ];
+
+        let agendas = Agenda::for_humans(&config, &schedule, NaiveDate::from_ymd_opt(2023, 1, 3).unwrap().and_hms_opt(12, 0, 0).unwrap());
+        assert_eq!(agendas.iter().map(|agenda| agenda.human.as_str()).collect::<Vec<_>>(), vec!["alice@example.com", "bob@example.com", "claire@example.com"]);
+
+        let alice = &agendas[0];
+        assert_eq!(alice.shifts.len(), 3, "the schedule should be ordered and merged into blocks before it is grouped by human");
+        assert_eq!(alice.total, Duration::days(4));
+        assert_eq!(alice.next.as_ref().map(|next| next.time.start), Some(schedule[1].time.start), "a shift which is underway is the next shift");
+        assert_eq!(alice.longest_gap, Some(Duration::days(3)));
+
+        let claire = &agendas[2];
+        assert!(claire.shifts.is_empty() && claire.next.is_none() && claire.longest_gap.is_none());
+    }
+}
@@ -0,0 +1,144 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{config::Config, solver::{ScheduleSlot, Scheduler}, summary::{Fairness, Summary}};
+
+/// A schedule generated with a particular tie-breaking seed, along with the metrics used to compare it
+/// against the other alternatives.
+#[derive(Debug, Clone, Serialize)]
+pub struct Alternative {
+    /// The seed used to break ties, or `None` if ties were broken by name. Passing this seed to
+    /// [`Scheduler::with_seed`] will reproduce the schedule.
+    pub seed: Option<u64>,
+    #[serde(skip)]
+    pub schedule: Vec<ScheduleSlot>,
+    /// The number of slots which could not be assigned to anyone.
+    pub unassigned: usize,
+    pub fairness: Fairness,
+    /// The difference between the most and least loaded humans, in hours (excluding unassigned slots).
+    #[serde(rename = "workloadSpread")]
+    pub workload_spread: i64,
+    /// The longest continuous period anybody is on-call for, in hours.
+    #[serde(rename = "longestShift")]
+    pub longest_shift: i64,
+}
+
+impl Alternative {
+    fn new(config: &Config, seed: Option<u64>, schedule: Vec<ScheduleSlot>) -> Self {
+        let summary = Summary::from(&schedule).with_adjustments(config);
+        // Only configured humans count towards the spread, so that unassigned slots don't inflate it
+        let workloads: Vec<i64> = config.humans.keys().map(|human| summary.workload(human).num_hours()).collect();
+        let (min, max) = (workloads.iter().min().copied().unwrap_or_default(), workloads.iter().max().copied().unwrap_or_default());
+        let (_, _, longest_shift) = summary.longest_shift_stats();
+
+        Self {
+            seed,
+            unassigned: schedule.iter().filter(|slot| slot.human.is_none()).count(),
+            schedule,
+            fairness: summary.fairness(),
+            workload_spread: max - min,
+            longest_shift,
+        }
+    }
+
+    /// Compares two alternatives, with the fairest ordered first. Schedules which leave fewer slots
+    /// unassigned are always preferred, followed by those with the lowest Gini coefficient, standard
+    /// deviation, workload spread and longest shift (in that order).
+    pub fn compare(&self, other: &Self) -> Ordering {
+        self.unassigned.cmp(&other.unassigned)
+            .then(self.fairness.gini.total_cmp(&other.fairness.gini))
+            .then(self.fairness.stddev.total_cmp(&other.fairness.stddev))
+            .then(self.workload_spread.cmp(&other.workload_spread))
+            .then(self.longest_shift.cmp(&other.longest_shift))
+    }
+
+    /// Generates a schedule with the default (name based) tie-breaking, along with `count` more using
+    /// the seeds `0..count`, returning them ordered from the fairest to the least fair. Seeds which
+    /// produce the same schedule as an earlier one are skipped, so each alternative is distinct.
+    pub fn generate(config: &Config, start: DateTime<Utc>, end: DateTime<Utc>, count: u64) -> Vec<Self> {
+        let mut seen = HashSet::new();
+        let mut alternatives: Vec<Self> = std::iter::once(None).chain((0..count).map(Some))
+            .filter_map(|seed| {
+                let mut scheduler = Scheduler::new(config);
+                if let Some(seed) = seed {
+                    scheduler = scheduler.with_seed(seed);
+                }
+
+                let schedule = scheduler.schedule(start, end);
+                seen.insert(schedule.clone()).then(|| Self::new(config, seed, schedule))
+            })
+            .collect();
+
+        alternatives.sort_by(Self::compare);
+        alternatives
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::{config::Human, timerange::TimeRange};
+
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default())
+            .with_human("claire@example.com", Human::default().with_prior_workload(Duration::hours(12)));
+
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let alternatives = Alternative::generate(&config, start, start + Duration::days(10), 5);
+
+        assert!(alternatives.len() <= 6, "at most one alternative should be generated for each seed, alongside the default");
+        assert_eq!(
+            alternatives.iter().map(|alternative| &alternative.schedule).collect::<HashSet<_>>().len(),
+            alternatives.len(),
+            "seeds which produce the same schedule should only be included once"
+        );
+        assert!(alternatives.windows(2).all(|pair| pair[0].compare(&pair[1]) != Ordering::Greater), "alternatives should be ordered from fairest to least fair");
+        assert!(alternatives.iter().any(|alternative| alternative.seed.is_none()));
+
+        let best = &alternatives[0];
+        let mut scheduler = Scheduler::new(&config);
+        if let Some(seed) = best.seed {
+            scheduler = scheduler.with_seed(seed);
+        }
+
+        assert_eq!(scheduler.schedule(start, start + Duration::days(10)), best.schedule, "the seed should reproduce the schedule");
+    }
+
+    #[test]
+    fn test_generate_skips_duplicates() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default());
+
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let alternatives = Alternative::generate(&config, start, start + Duration::days(7), 5);
+
+        assert_eq!(alternatives.len(), 1, "with only one human, every seed produces the same schedule");
+        assert_eq!(alternatives[0].seed, None, "the default schedule should be kept over identical seeded ones");
+    }
+
+    #[test]
+    fn test_spread_excludes_unassigned() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default());
+
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let schedule = vec![
+            ScheduleSlot { time: TimeRange::new(start, start + Duration::days(1)), human: Some("alice@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::days(1), start + Duration::days(2)), human: Some("bob@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::days(2), start + Duration::days(5)), human: None },
+        ];
+
+        let alternative = Alternative::new(&config, None, schedule);
+        assert_eq!(alternative.unassigned, 1);
+        assert_eq!(alternative.workload_spread, 0, "unassigned slots should not count towards the spread");
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::Duration;
+use serde::Serialize;
+
+use crate::{solver::ScheduleSlot, timerange::TimeRange};
+
+/// A continuous period during which the same human is on-call, made up of one or more contiguous slots.
+/// Slots separated by a gap (like business hours on consecutive days) always form separate blocks, so a
+/// block never covers time during which nobody is on-call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// The period from the start of the first slot to the end of the last slot in this block.
+    pub time: TimeRange,
+    /// The total on-call time within this block, which is the same as the length of its time range.
+    pub duration: Duration,
+    pub human: Option<String>,
+    /// The slots which make up this block, in the order they appeared in the schedule.
+    pub slots: Vec<TimeRange>,
+}
+
+impl Block {
+    /// Merges consecutive slots for the same human into blocks, where each slot starts exactly when the
+    /// previous one ended. The schedule is expected to be ordered by time, as it is when generated by the scheduler.
+    pub fn merge(schedule: &[ScheduleSlot]) -> Vec<Block> {
+        let mut blocks: Vec<Block> = Vec::new();
+        for slot in schedule {
+            match blocks.last_mut() {
+                Some(block) if block.human == slot.human && block.time.end == slot.time.start => {
+                    block.time.end = slot.time.end;
+                    block.duration += slot.time.len();
+                    block.slots.push(slot.time);
+                },
+                _ => blocks.push(Block::from(slot)),
+            }
+        }
+
+        blocks
+    }
+}
+
+impl From<&ScheduleSlot> for Block {
+    fn from(slot: &ScheduleSlot) -> Self {
+        Self {
+            time: slot.time,
+            duration: slot.time.len(),
+            human: slot.human.clone(),
+            slots: vec![slot.time],
+        }
+    }
+}
+
+impl Serialize for Block {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Block", 5)?;
+        state.serialize_field("start", &self.time.start)?;
+        state.serialize_field("end", &self.time.end)?;
+        state.serialize_field("human", &self.human)?;
+        state.serialize_field("hours", &(self.duration.num_seconds() as f64 / 3600.0))?;
+        state.serialize_field("slots", &self.slots)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let slot = |from: i64, to: i64, human: Option<&str>| ScheduleSlot {
+            time: TimeRange::new(start + Duration::hours(from), start + Duration::hours(to)),
+            human: human.map(|h| h.to_string()),
+        };
+
+        let schedule = vec![
+            slot(8, 12, Some("alice@example.com")),
+            slot(12, 16, Some("alice@example.com")),
+            slot(16, 24, None),
+            slot(24, 32, Some("alice@example.com")),
+            slot(32, 40, Some("alice@example.com")),
+            slot(56, 64, Some("alice@example.com")),
+        ];
+
+        let blocks = Block::merge(&schedule);
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0].time, TimeRange::new(schedule[0].time.start, schedule[1].time.end));
+        assert_eq!(blocks[0].duration, Duration::hours(8));
+        assert_eq!(blocks[0].slots, vec![schedule[0].time, schedule[1].time]);
+        assert_eq!(blocks[1].human, None);
+        assert_eq!(blocks[2].duration, Duration::hours(16));
+        assert_eq!(blocks[3].time, schedule[5].time, "slots separated by a gap should not be merged");
+    }
+}
@@ -1,17 +1,180 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::BTreeMap, fmt::Display, path::{Path, PathBuf}};
 
-use chrono::Duration;
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use serde::{Serialize, Deserialize};
 
-use crate::constraints::Constraint;
+use crate::{constraints::Constraint, history, timerange::TimeRange, validation, Error};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
     #[serde(rename = "shiftLength", with="duration_days")]
     pub shift_length: Duration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handoff: Option<Handoff>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<History>,
     #[serde(default)]
     pub constraints: Vec<Constraint>,
-    pub humans: HashMap<String, Human>,
+    pub humans: BTreeMap<String, Human>,
+}
+
+impl Config {
+    /// Creates a new configuration with the given shift length, no constraints and no humans.
+    pub fn new(shift_length: Duration) -> Self {
+        Self {
+            shift_length,
+            handoff: None,
+            history: None,
+            constraints: Vec::new(),
+            humans: BTreeMap::new(),
+        }
+    }
+
+    /// Parses a configuration from its YAML representation.
+    pub fn parse(source: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(source)
+    }
+
+    /// Reads and parses the configuration file, returning it alongside its original contents
+    /// so that any problems can later be reported against the relevant lines.
+    pub fn read(path: &Path) -> Result<(Self, String), Error> {
+        let source = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let config = Self::parse(&source).map_err(|source| Error::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        Ok((config, source))
+    }
+
+    /// Reads the configuration file for a schedule starting at the given time, adding the workload
+    /// from its history (if configured) to each human's prior workload.
+    pub fn read_at(path: &Path, at: NaiveDateTime) -> Result<Self, Error> {
+        let (config, _) = Self::read(path)?;
+
+        history::apply(config, path.parent().unwrap_or(Path::new("")), at)
+    }
+
+    /// Reads the configuration file like [`Config::read_at`], failing if it has any errors which would
+    /// prevent a schedule from being generated between `start` and `end`. Any warnings are returned
+    /// (described against the file) so that they can be reported, but are left for [`validate`](crate::validate)
+    /// to reject.
+    pub fn read_valid(path: &Path, start: NaiveDateTime, end: NaiveDateTime) -> Result<(Self, Vec<String>), Error> {
+        let (config, source) = Self::read(path)?;
+
+        let problems = validation::validate(&config, start, end);
+        if problems.iter().any(|problem| problem.is_error()) {
+            return Err(Error::Validation {
+                path: path.to_path_buf(),
+                source,
+                problems,
+            });
+        }
+
+        let warnings = problems.iter().map(|problem| problem.describe(path, &source)).collect();
+        Ok((history::apply(config, path.parent().unwrap_or(Path::new("")), start)?, warnings))
+    }
+
+    pub fn with_handoff(self, handoff: Handoff) -> Self {
+        Self {
+            handoff: Some(handoff),
+            ..self
+        }
+    }
+
+    pub fn with_history(self, history: History) -> Self {
+        Self {
+            history: Some(history),
+            ..self
+        }
+    }
+
+    pub fn with_constraint(mut self, constraint: Constraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    pub fn with_human<S: Into<String>>(mut self, name: S, human: Human) -> Self {
+        self.humans.insert(name.into(), human);
+        self
+    }
+
+    /// Returns the time slots between `start` and `end` which require on-call coverage.
+    pub fn slots(&self, start: NaiveDateTime, end: NaiveDateTime) -> Vec<TimeRange> {
+        let initial_range: Box<dyn Iterator<Item = TimeRange>> = Box::new(vec![TimeRange::new(start, end)].into_iter());
+
+        self.constraints.iter().fold(initial_range, |ranges, constraint| {
+            constraint.flat_map(ranges)
+        }).collect()
+    }
+}
+
+/// Controls when shifts hand off from one human to the next. Shift boundaries fall every
+/// `shiftLength` after the anchor, so they remain stable regardless of the schedule's start date.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Handoff {
+    /// The date from which shift boundaries are calculated, defaults to 1970-01-01.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<NaiveDate>,
+    /// The day of the week on which shifts hand off, the anchor is moved forward to the first
+    /// occurrence of this day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<Weekday>,
+    /// The time of day at which shifts hand off, defaults to midnight. This is a local time in the
+    /// `offset` timezone, or UTC if no offset is configured.
+    #[serde(default)]
+    pub time: NaiveTime,
+    /// The fixed offset from UTC (like `+02:00`) in which the anchor and handoff time are expressed.
+    /// The schedule itself is always generated in UTC.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "utc_offset")]
+    pub offset: Option<FixedOffset>,
+}
+
+impl Handoff {
+    /// Calculates the point in time from which all shift boundaries are derived.
+    pub fn origin(&self) -> NaiveDateTime {
+        let mut anchor = self.anchor.unwrap_or_default();
+
+        if let Some(weekday) = self.weekday {
+            let offset = (7 + weekday.num_days_from_monday() as i64 - anchor.weekday().num_days_from_monday() as i64) % 7;
+            anchor += Duration::days(offset);
+        }
+
+        let local_minus_utc = self.offset.map(|offset| offset.local_minus_utc()).unwrap_or_default();
+        anchor.and_time(self.time) - Duration::seconds(local_minus_utc as i64)
+    }
+}
+
+/// Points at a directory of previously published schedules, from which each human's prior
+/// workload is calculated automatically when the configuration is loaded with [`Config::read_at`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct History {
+    /// The directory containing the previously published schedules, relative to the configuration file.
+    pub path: PathBuf,
+    /// When set, on-call time counts half as much each time this period elapses, so that
+    /// older schedules have less influence than recent ones.
+    #[serde(rename = "halfLife", default, skip_serializing_if = "Option::is_none", with = "optional_duration_days")]
+    pub half_life: Option<Duration>,
+}
+
+impl History {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            half_life: None,
+        }
+    }
+
+    pub fn with_half_life(self, half_life: Duration) -> Self {
+        Self {
+            half_life: Some(half_life),
+            ..self
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -20,11 +183,31 @@ pub struct Human {
     pub constraints: Vec<Constraint>,
     #[serde(rename = "priorWorkload", with="duration_hours", default="Duration::zero")]
     pub prior_workload: Duration,
+    /// The fraction of a full-time workload this human is expected to carry (e.g. `0.6` for
+    /// someone working three days a week). Fairness is measured relative to this value.
+    #[serde(default="default_capacity")]
+    pub capacity: f64,
+}
+
+fn default_capacity() -> f64 {
+    1.0
 }
 
-#[cfg(test)]
-#[allow(dead_code)]
 impl Human {
+    /// Returns a vector of booleans indicating whether each slot can be covered by the given constraints.
+    pub fn possible_coverage(&self, slots: &[TimeRange]) -> Vec<bool> {
+        let initial_slots: Box<dyn Iterator<Item = TimeRange>> = Box::new(slots.iter().copied());
+
+        let available_slots = self.constraints.iter().fold(initial_slots, |ranges, constraint| {
+            constraint.flat_map(ranges)
+        }).collect::<Vec<_>>();
+
+        slots
+            .iter()
+            .map(|slot| available_slots.binary_search(slot).is_ok())
+            .collect()
+    }
+
     pub fn with_constraints(self, constraints: Vec<Constraint>) -> Self {
         Self {
             constraints,
@@ -38,6 +221,13 @@ impl Human {
             ..self
         }
     }
+
+    pub fn with_capacity(self, capacity: f64) -> Self {
+        Self {
+            capacity,
+            ..self
+        }
+    }
 }
 
 impl Default for Human {
@@ -45,6 +235,7 @@ impl Default for Human {
         Self {
             constraints: Vec::new(),
             prior_workload: Duration::zero(),
+            capacity: default_capacity(),
         }
     }
 }
@@ -57,6 +248,10 @@ impl Display for Human {
             info.push(format!("prior workload: {} hours", self.prior_workload.num_hours()));
         }
 
+        if self.capacity != 1.0 {
+            info.push(format!("capacity: {:.0}%", self.capacity * 100.0));
+        }
+
         for constraint in self.constraints.iter() {
             info.push(format!("{}", constraint));
         }
@@ -67,47 +262,121 @@ impl Display for Human {
     }
 }
 
+/// Accepts either a whole number of days or an ISO 8601 duration (e.g. `PT12H`).
 mod duration_days {
     use chrono::Duration;
     use serde::Deserialize;
 
+    use super::iso8601_duration::DurationValue;
+
+    /// Whole numbers of days are written as plain numbers (as they always have been), falling back
+    /// to an ISO 8601 duration for anything more precise.
     pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
     where S: serde::Serializer {
-        let days = duration.num_days();
-        serializer.serialize_u64(days as u64)
+        if duration.num_seconds() >= 0 && duration.num_seconds() % 86400 == 0 && duration.subsec_nanos() == 0 {
+            serializer.serialize_u64(duration.num_days() as u64)
+        } else {
+            super::iso8601_duration::serialize(duration, serializer)
+        }
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
     where D: serde::Deserializer<'de> {
-        let days = u64::deserialize(deserializer)?;
-        Ok(Duration::days(days as i64))
+        let duration = match DurationValue::deserialize(deserializer)? {
+            DurationValue::Number(days) => Duration::days(days as i64),
+            DurationValue::Iso8601(s) => super::iso8601_duration::parse(&s).map_err(serde::de::Error::custom)?,
+        };
+
+        if duration <= Duration::zero() {
+            return Err(serde::de::Error::custom("the duration must be greater than zero"));
+        }
+
+        Ok(duration)
     }
 }
 
+/// Reads and writes an optional UTC offset like `+02:00` or `-05:30`.
+mod utc_offset {
+    use chrono::FixedOffset;
+    use serde::Deserialize;
+
+    pub fn serialize<S>(offset: &Option<FixedOffset>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: serde::Serializer {
+        match offset {
+            Some(offset) => serializer.serialize_str(&offset.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<FixedOffset>, D::Error>
+    where D: serde::Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map(Some).map_err(|_| serde::de::Error::custom(format!("Invalid UTC offset '{}', offsets must be specified like '+02:00' or '-05:30'", s)))
+    }
+}
+
+/// An optional version of [`duration_days`].
+mod optional_duration_days {
+    use chrono::Duration;
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: serde::Serializer {
+        match duration {
+            Some(duration) => super::duration_days::serialize(duration, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where D: serde::Deserializer<'de> {
+        super::duration_days::deserialize(deserializer).map(Some)
+    }
+}
+
+/// Accepts either a whole number of hours or an ISO 8601 duration (e.g. `P2DT4H`).
 mod duration_hours {
     use chrono::Duration;
     use serde::Deserialize;
 
+    use super::iso8601_duration::DurationValue;
+
+    /// Whole numbers of hours are written as plain numbers (as they always have been), falling back
+    /// to an ISO 8601 duration for anything more precise.
     pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
     where S: serde::Serializer {
-        let hours = duration.num_hours();
-        serializer.serialize_u64(hours as u64)
+        if duration.num_seconds() >= 0 && duration.num_seconds() % 3600 == 0 && duration.subsec_nanos() == 0 {
+            serializer.serialize_u64(duration.num_hours() as u64)
+        } else {
+            super::iso8601_duration::serialize(duration, serializer)
+        }
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
     where D: serde::Deserializer<'de> {
-        let hours = u64::deserialize(deserializer)?;
-        Ok(Duration::hours(hours as i64))
+        match DurationValue::deserialize(deserializer)? {
+            DurationValue::Number(hours) => Ok(Duration::hours(hours as i64)),
+            DurationValue::Iso8601(s) => super::iso8601_duration::parse(&s).map_err(serde::de::Error::custom),
+        }
     }
 }
 
-#[allow(unused)]
 mod iso8601_duration {
     use std::{iter::Peekable, str::Chars};
 
     use chrono::Duration;
     use serde::Deserialize;
 
+    const FORMAT_ERROR: &str = "Invalid duration format, durations must be specified in ISO8601 format like 'P1DT1H'";
+
+    /// A duration which may be expressed either as a plain number (whose unit depends on the field)
+    /// or as an ISO 8601 duration string.
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub enum DurationValue {
+        Number(u64),
+        Iso8601(String),
+    }
+
     pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
     where S: serde::Serializer {
         let days = duration.num_seconds() / 86400;
@@ -144,15 +413,23 @@ mod iso8601_duration {
             }
         }
 
+        if s == "P" {
+            s.push_str("T0S");
+        }
+
         serializer.serialize_str(&s)
     }
 
+    #[allow(unused)]
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
     where D: serde::Deserializer<'de> {
         let s = String::deserialize(deserializer)?;
-        
+        parse(&s).map_err(serde::de::Error::custom)
+    }
+
+    pub fn parse(s: &str) -> Result<Duration, String> {
         if !s.starts_with('P') {
-            return Err(serde::de::Error::custom("Invalid duration format, durations must be specified in ISO8601 format like 'P1DT1H'"));
+            return Err(FORMAT_ERROR.to_string());
         }
 
         let mut chars = s.chars().peekable();
@@ -161,7 +438,7 @@ mod iso8601_duration {
             chars.next();
         }
 
-        let read_number = |chars: &mut Peekable<Chars>| -> Result<Option<u64>, D::Error> {
+        let read_number = |chars: &mut Peekable<Chars>| -> Result<Option<u64>, String> {
             let mut number = String::new();
             while let Some(c) = chars.peek() {
                 if c.is_ascii_digit() {
@@ -175,13 +452,16 @@ mod iso8601_duration {
                 return Ok(None);
             }
 
-            number.parse().map(Some).map_err(serde::de::Error::custom)
+            number.parse().map(Some).map_err(|e| format!("{}", e))
         };
 
         let mut duration = Duration::zero();
+        let mut segments = 0;
         while let Some(n) = read_number(&mut chars)? {
+            segments += 1;
             let adjustment = match chars.next() {
-                None => return Err(serde::de::Error::custom("Invalid duration format, durations must be specified in ISO8601 format like 'P1DT1H'")),
+                None => return Err(FORMAT_ERROR.to_string()),
+                Some('W') => Duration::weeks(n as i64),
                 Some('D') => {
                     if chars.peek() == Some(&'T') {
                         chars.next();
@@ -191,25 +471,33 @@ mod iso8601_duration {
                 },
                 Some('H') => Duration::hours(n as i64),
                 Some('M') => Duration::minutes(n as i64),
-                Some('S') => {
-                    if chars.peek() == Some(&'.') {
-                        chars.next();
+                Some('S') => Duration::seconds(n as i64),
+                Some('.') => {
+                    let mut fraction = String::new();
+                    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                        fraction.push(c);
+                    }
+
+                    if fraction.is_empty() {
+                        return Err(format!("{} (encountered a decimal point without any digits following it)", FORMAT_ERROR));
+                    }
 
-                        if let Some(millis) = read_number(&mut chars)? {
-                            Duration::seconds(n as i64) + Duration::milliseconds(millis as i64)
-                        } else {
-                            return Err(serde::de::Error::custom("Invalid duration format, durations must be specified in ISO8601 format like 'P1DT1H' (encountered a decimal point without any digits following it)"));
-                        }
-                    } else {
-                        Duration::seconds(n as i64)
+                    if chars.next() != Some('S') {
+                        return Err(format!("{} (fractional values are only supported for seconds)", FORMAT_ERROR));
                     }
+
+                    let millis: i64 = format!("{:0<3.3}", fraction).parse().map_err(|e| format!("{}", e))?;
+                    Duration::seconds(n as i64) + Duration::milliseconds(millis)
                 },
-                Some(c) => return Err(serde::de::Error::custom(format!("Invalid duration format, durations must be specified in ISO8601 format like 'P1DT1H' (encountered an unrecognized segment type '{}')", c))),
+                Some(c) => return Err(format!("{} (encountered an unrecognized segment type '{}')", FORMAT_ERROR, c)),
             };
 
-            duration = duration + adjustment;
+            duration += adjustment;
         }
 
+        if chars.next().is_some() || segments == 0 {
+            return Err(FORMAT_ERROR.to_string());
+        }
 
         Ok(duration)
     }
@@ -233,6 +521,12 @@ mod tests {
         assert_eq!(serde_yaml::from_str::<DurationDemo>("duration: PT10H").unwrap().duration, Duration::hours(10));
         assert_eq!(serde_yaml::from_str::<DurationDemo>("duration: P1DT10H").unwrap().duration, Duration::days(1) + Duration::hours(10));
         assert_eq!(serde_yaml::from_str::<DurationDemo>("duration: P1DT10H10M10S").unwrap().duration, Duration::days(1) + Duration::hours(10) + Duration::minutes(10) + Duration::seconds(10));
+        assert_eq!(serde_yaml::from_str::<DurationDemo>("duration: PT10.5S").unwrap().duration, Duration::seconds(10) + Duration::milliseconds(500));
+        assert_eq!(serde_yaml::from_str::<DurationDemo>("duration: P1W").unwrap().duration, Duration::weeks(1));
+        assert!(serde_yaml::from_str::<DurationDemo>("duration: 10H").is_err());
+        assert!(serde_yaml::from_str::<DurationDemo>("duration: PT10X").is_err());
+        assert!(serde_yaml::from_str::<DurationDemo>("duration: P").is_err());
+        assert!(serde_yaml::from_str::<DurationDemo>("duration: PT").is_err());
     }
 
     #[test]
@@ -260,6 +554,7 @@ mod tests {
                 constraints:
                     - !None
                 priorWorkload: 72
+                capacity: 0.6
             bob@example.com:
                 constraints:
                     - !Unavailable
@@ -271,5 +566,86 @@ mod tests {
         assert_eq!(config.shift_length, Duration::days(1));
         assert_eq!(config.constraints.len(), 2);
         assert_eq!(config.humans.len(), 2);
+        assert_eq!(config.humans["alice@example.com"].capacity, 0.6);
+        assert_eq!(config.humans["bob@example.com"].capacity, 1.0);
+    }
+
+    #[test]
+    fn config_deserialize_iso8601_durations()
+    {
+        let config = r#"
+        shiftLength: PT12H
+        humans:
+            alice@example.com:
+                priorWorkload: P1DT12H
+            bob@example.com:
+                priorWorkload: 12
+        "#;
+
+        let config: Config = serde_yaml::from_str(config).expect("the config should be deserializable");
+        assert_eq!(config.shift_length, Duration::hours(12));
+        assert_eq!(config.humans["alice@example.com"].prior_workload, Duration::hours(36));
+        assert_eq!(config.humans["bob@example.com"].prior_workload, Duration::hours(12));
+
+        assert!(serde_yaml::from_str::<Config>("shiftLength: PT0S\nhumans: {}").is_err(), "shifts must have a length");
+        assert!(serde_yaml::from_str::<Config>("shiftLength: 0\nhumans: {}").is_err(), "shifts must have a length");
+    }
+
+    #[test]
+    fn config_serialize_durations()
+    {
+        let config = Config::new(Duration::days(7))
+            .with_human("alice@example.com", Human::default().with_prior_workload(Duration::hours(72)))
+            .with_human("bob@example.com", Human::default().with_prior_workload(Duration::minutes(90)));
+
+        let serialized = serde_yaml::to_string(&config).unwrap();
+        assert!(serialized.contains("shiftLength: 7\n"), "whole days should keep being written as numbers");
+        assert!(serialized.contains("priorWorkload: 72\n"), "whole hours should keep being written as numbers");
+        assert!(serialized.contains("priorWorkload: PT1H30M\n"), "partial hours should be written as ISO 8601 durations");
+
+        let config = Config::new(Duration::hours(12));
+        assert!(serde_yaml::to_string(&config).unwrap().contains("shiftLength: PT12H\n"));
+    }
+
+    #[test]
+    fn config_deserialize_handoff()
+    {
+        let config = r#"
+        shiftLength: P7D
+        handoff:
+            anchor: 2023-01-04
+            weekday: Mon
+            time: 10:00:00
+        humans:
+            alice@example.com: {}
+        "#;
+
+        let config: Config = serde_yaml::from_str(config).expect("the config should be deserializable");
+        let handoff = config.handoff.expect("the handoff should be configured");
+        assert_eq!(handoff.origin(), NaiveDate::from_ymd_opt(2023, 1, 9).unwrap().and_hms_opt(10, 0, 0).unwrap());
+
+        let config: Config = serde_yaml::from_str("shiftLength: P7D\nhandoff: { anchor: 2023-01-02, time: 01:00:00, offset: '+02:00' }\nhumans: {}").unwrap();
+        assert_eq!(config.handoff.unwrap().origin(), NaiveDate::from_ymd_opt(2023, 1, 1).unwrap().and_hms_opt(23, 0, 0).unwrap(), "the handoff should be converted to UTC");
+
+        assert!(serde_yaml::from_str::<Config>("shiftLength: P7D\nhandoff: { offset: CET }\nhumans: {}").is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn config_deserialize_history()
+    {
+        let config = r#"
+        shiftLength: 7
+        history:
+            path: ./history
+            halfLife: 90
+        humans:
+            alice@example.com: {}
+        "#;
+
+        let config: Config = serde_yaml::from_str(config).expect("the config should be deserializable");
+        assert_eq!(config.history, Some(History::new("./history").with_half_life(Duration::days(90))));
+
+        let config: Config = serde_yaml::from_str("shiftLength: 7\nhistory: { path: history }\nhumans: {}").unwrap();
+        assert_eq!(config.history.and_then(|h| h.half_life), None);
+    }
+}
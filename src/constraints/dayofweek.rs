@@ -101,4 +101,28 @@ mod tests {
             "The iterator should generate the correct sequence of ranges"
         );
     }
+
+    #[test]
+    fn test_excluded_ranges() {
+        let output: Vec<TimeRange> = DayOfWeekIterator::new(
+            vec![
+                TimeRange::new(date_time!(2020, 1, 4, 8, 0, 0), date_time!(2020, 1, 4, 16, 0, 0)),
+                TimeRange::new(date_time!(2020, 1, 5, 8, 0, 0), date_time!(2020, 1, 5, 16, 0, 0)),
+                TimeRange::new(date_time!(2020, 1, 6, 8, 0, 0), date_time!(2020, 1, 6, 16, 0, 0)),
+            ]
+            .into_iter(),
+            vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+        )
+        .collect();
+
+        let expected = vec![TimeRange::new(
+            date_time!(2020, 1, 6, 8, 0, 0),
+            date_time!(2020, 1, 6, 16, 0, 0),
+        )];
+
+        assert_eq!(
+            output, expected,
+            "The iterator should continue past ranges which fall entirely on excluded days"
+        );
+    }
 }
@@ -25,12 +25,13 @@ macro_rules! constraint_iterator {
             type Item = $crate::timerange::TimeRange;
 
             fn next(&mut self) -> Option<Self::Item> {
-                if self.buffer.is_empty() {
+                // Segments may not produce any ranges, so keep consuming the source until one does
+                while self.buffer.is_empty() {
                     match self.source.next() {
                         Some(range) => {
                             self.buffer.extend(self.segment(range));
                         },
-                        None => {},
+                        None => break,
                     }
                 }
 
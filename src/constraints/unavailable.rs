@@ -7,6 +7,7 @@ constraint_iterator!(UnavailableIterator(start: NaiveDate, end: NaiveDate) = |se
 
     match conflict {
         Some(conflict) if conflict.is_zero() => vec![range],
+        Some(conflict) if conflict == range => vec![],
         Some(conflict) if conflict.start != range.start && conflict.end != range.end => {
             vec![
                 TimeRange::new(range.start, conflict.start),
@@ -43,4 +44,22 @@ mod tests {
 
         assert_eq!(output, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unavailable_entire_range() {
+        let output: Vec<TimeRange> = UnavailableIterator::new(
+            vec![
+                TimeRange::new(date_time!(2020, 1, 1, 8, 0, 0), date_time!(2020, 1, 1, 16, 0, 0)),
+                TimeRange::new(date_time!(2020, 1, 2, 8, 0, 0), date_time!(2020, 1, 2, 16, 0, 0)),
+            ].into_iter(),
+            date!(2020, 1, 1),
+            date!(2020, 1, 2),
+        ).collect();
+
+        let expected = vec![
+            TimeRange::new(date_time!(2020, 1, 2, 8, 0, 0), date_time!(2020, 1, 2, 16, 0, 0)),
+        ];
+
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+
+use crate::{solver::ScheduleSlot, timerange::TimeRange};
+
+/// A period of time whose assignee differs between two schedules. `None` indicates that nobody
+/// was on-call for that period, either because it was unassigned or because it was not part of the schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    #[serde(flatten)]
+    pub time: TimeRange,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// The total on-call time for a human in each of the schedules being compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HumanChange {
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl HumanChange {
+    /// The amount of on-call time gained (positive) or lost (negative) by this human.
+    pub fn net(&self) -> Duration {
+        self.after - self.before
+    }
+}
+
+impl Serialize for HumanChange {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let hours = |d: Duration| d.num_seconds() as f64 / 3600.0;
+
+        let mut state = serializer.serialize_struct("HumanChange", 3)?;
+        state.serialize_field("before", &hours(self.before))?;
+        state.serialize_field("after", &hours(self.after))?;
+        state.serialize_field("net", &hours(self.net()))?;
+        state.end()
+    }
+}
+
+/// The differences between two versions of a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+    pub humans: BTreeMap<String, HumanChange>,
+}
+
+impl Diff {
+    /// Compares two schedules, identifying the periods whose assignee changed. The schedules do not
+    /// need to be split into the same slots, as they are compared over the union of their boundaries.
+    pub fn between(before: &[ScheduleSlot], after: &[ScheduleSlot]) -> Self {
+        let mut boundaries: Vec<NaiveDateTime> = before.iter().chain(after.iter())
+            .flat_map(|slot| [slot.time.start, slot.time.end])
+            .collect();
+        boundaries.sort();
+        boundaries.dedup();
+
+        let assignee = |schedule: &[ScheduleSlot], period: &TimeRange| -> Option<String> {
+            schedule.iter()
+                .find(|slot| slot.time.start <= period.start && slot.time.end >= period.end)
+                .and_then(|slot| slot.human.clone())
+        };
+
+        let mut changes: Vec<Change> = Vec::new();
+        for window in boundaries.windows(2) {
+            let period = TimeRange::new(window[0], window[1]);
+            let (was, now) = (assignee(before, &period), assignee(after, &period));
+
+            if was == now {
+                continue;
+            }
+
+            match changes.last_mut() {
+                Some(last) if last.time.end == period.start && last.before == was && last.after == now => {
+                    last.time.end = period.end;
+                },
+                _ => changes.push(Change { time: period, before: was, after: now }),
+            }
+        }
+
+        let mut humans: BTreeMap<String, HumanChange> = BTreeMap::new();
+        for slot in before {
+            if let Some(human) = slot.human.as_ref() {
+                humans.entry(human.clone()).or_default().before += slot.time.len();
+            }
+        }
+
+        for slot in after {
+            if let Some(human) = slot.human.as_ref() {
+                humans.entry(human.clone()).or_default().after += slot.time.len();
+            }
+        }
+
+        Self { changes, humans }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn slot(day: u32, hours: i64, human: Option<&str>) -> ScheduleSlot {
+        let start = NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        ScheduleSlot {
+            time: TimeRange::new(start, start + Duration::hours(hours)),
+            human: human.map(|h| h.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = vec![
+            slot(2, 24, Some("alice@example.com")),
+            slot(3, 24, Some("alice@example.com")),
+            slot(4, 24, Some("bob@example.com")),
+        ];
+
+        let after = vec![
+            slot(2, 48, Some("claire@example.com")),
+            slot(4, 24, Some("bob@example.com")),
+            slot(5, 24, None),
+        ];
+
+        let diff = Diff::between(&before, &after);
+
+        assert_eq!(diff.changes, vec![Change {
+            time: TimeRange::new(slot(2, 0, None).time.start, slot(4, 0, None).time.start),
+            before: Some("alice@example.com".into()),
+            after: Some("claire@example.com".into()),
+        }]);
+
+        assert_eq!(diff.humans["alice@example.com"].net(), Duration::hours(-48));
+        assert_eq!(diff.humans["bob@example.com"].net(), Duration::zero());
+        assert_eq!(diff.humans["claire@example.com"].net(), Duration::hours(48));
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let schedule = vec![slot(2, 24, Some("alice@example.com"))];
+        assert!(Diff::between(&schedule, &schedule).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fmt::Display, path::PathBuf, process::ExitCode};
+
+use chrono::NaiveDateTime;
+
+use crate::{input::InputError, output::TemplateError, timerange::TimeRange, validation::Problem};
+
+/// The errors which may be encountered while generating a schedule, grouped by the stage
+/// at which they occurred so that each class of failure can be reported with its own exit code.
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read or written.
+    Io { path: PathBuf, source: std::io::Error },
+    /// The configuration file could not be parsed.
+    Parse { path: PathBuf, source: serde_yaml::Error },
+    /// A previously generated schedule could not be parsed.
+    Input { path: PathBuf, source: InputError },
+    /// A template used by the template output format could not be parsed.
+    Template { path: PathBuf, source: TemplateError },
+    /// The configuration file was parsed but contains problems, `source` holds the original
+    /// file contents so that each problem can be located within it.
+    Validation { path: PathBuf, source: String, problems: Vec<Problem> },
+    /// A human named on the command line is not part of the configuration.
+    UnknownHuman { path: PathBuf, human: String },
+    /// A human asked to swap a slot which they are not on-call for.
+    NotOnCall { path: PathBuf, human: String, at: NaiveDateTime },
+    /// The schedule was generated, but some of its slots could not be assigned to anyone.
+    Scheduling { unassigned: Vec<TimeRange> },
+    /// The output could not be written in the requested format.
+    Output(Box<dyn std::error::Error>),
+}
+
+impl Error {
+    /// The exit code which the CLI should return when it encounters this error.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::Scheduling { .. } => ExitCode::from(1),
+            Error::Io { .. } => ExitCode::from(3),
+            Error::Parse { .. } | Error::Input { .. } | Error::Template { .. } => ExitCode::from(4),
+            Error::Validation { .. } | Error::UnknownHuman { .. } | Error::NotOnCall { .. } => ExitCode::from(5),
+            Error::Output(_) => ExitCode::from(6),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, source } if source.kind() == std::io::ErrorKind::NotFound => {
+                write!(f, "error: the file '{}' could not be found, please check that the path is correct", path.display())
+            },
+            Error::Io { path, source } => {
+                write!(f, "error: unable to access '{}': {}", path.display(), source)
+            },
+            Error::Parse { path, source } => {
+                match source.location() {
+                    Some(location) => write!(f, "{}:{}:{}: error: ", path.display(), location.line(), location.column())?,
+                    None => write!(f, "{}: error: ", path.display())?,
+                }
+
+                writeln!(f, "{}", source)?;
+                write!(f, "Please check that this field matches the format described in the README.")
+            },
+            Error::Input { path, source } => {
+                match source.line {
+                    Some(line) => write!(f, "{}:{}: error: ", path.display(), line)?,
+                    None => write!(f, "{}: error: ", path.display())?,
+                }
+
+                writeln!(f, "{}", source.message)?;
+                write!(f, "Please check that this file is a schedule in one of the JSON, YAML or CSV output formats.")
+            },
+            Error::Template { path, source } => {
+                writeln!(f, "{}:{}: error: {}", path.display(), source.line, source.message)?;
+                write!(f, "Please check that this template only uses the tags described in the README.")
+            },
+            Error::Validation { path, source, problems } => {
+                for problem in problems {
+                    writeln!(f, "{}", problem.describe(path, source))?;
+                }
+
+                writeln!(f)?;
+                write!(f, "Found {} problem(s) in {}", problems.len(), path.display())
+            },
+            Error::UnknownHuman { path, human } => {
+                write!(f, "{}: error: {} is not one of the humans in this configuration", path.display(), human)
+            },
+            Error::NotOnCall { path, human, at } => {
+                write!(f, "{}: error: {} is not on-call at {}, so there is nothing to swap", path.display(), human, at)
+            },
+            Error::Scheduling { unassigned } => {
+                writeln!(f, "error: {} slot(s) in the schedule could not be assigned to anyone, this is likely due to constraints that are too restrictive.", unassigned.len())?;
+                for slot in unassigned.iter().take(5) {
+                    writeln!(f, "  {}", slot)?;
+                }
+
+                if unassigned.len() > 5 {
+                    writeln!(f, "  ...and {} more", unassigned.len() - 5)?;
+                }
+
+                write!(f, "You can use the validate command to check your configuration for problems.")
+            },
+            Error::Output(err) => {
+                write!(f, "error: unable to write the output: {}", err)
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { source, .. } => Some(source),
+            Error::Input { source, .. } => Some(source),
+            Error::Template { source, .. } => Some(source),
+            Error::Output(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use serde::Serialize;
 
 use crate::{config::Config, solver::ScheduleSlot, timerange::TimeRange};
 
@@ -7,7 +9,9 @@ mod length;
 mod recency;
 mod workload;
 
-pub fn all(config: &Config) -> Vec<Box<dyn Optimizer>> {
+/// Constructs the built-in optimizers, which are used by the [`crate::Scheduler`] by default.
+/// These are named `coverage`, `length`, `recency` and `workload`.
+pub(crate) fn all(config: &Config) -> Vec<Box<dyn Optimizer>> {
     vec![
         coverage::Coverage::init(config),
         length::Length::init(config),
@@ -16,27 +20,49 @@ pub fn all(config: &Config) -> Vec<Box<dyn Optimizer>> {
     ]
 }
 
-#[derive(Debug, Clone, Copy)]
+/// The cost reported by a single [`Optimizer`] for a [`Candidate`], along with the weight
+/// that cost carries relative to the other optimizers.
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct Cost {
     pub cost: f64,
     pub weight: f64,
 }
 
+/// An optimizer contributes a weighted cost to each candidate for a rotation, with the
+/// [`crate::Scheduler`] assigning the rotation to the candidates with the lowest total cost.
+///
+/// Custom optimizers can be registered using [`crate::Scheduler::with_optimizer`], and the
+/// built-in optimizers removed using [`crate::Scheduler::without_optimizer`].
 pub trait Optimizer {
+    /// Constructs the optimizer with its initial state derived from the configuration (for example,
+    /// the prior workload of each human).
     fn init(config: &Config) -> Box<dyn Optimizer>
     where
         Self: Sized;
 
+    /// A unique name for this optimizer, which is used to identify its cost in debug output
+    /// and to remove it from a scheduler.
     fn name(&self) -> &'static str;
 
+    /// The weight of this optimizer's cost relative to the other optimizers, defaults to `1.0`.
     fn weight(&self) -> f64 {
         1.0
     }
 
+    /// Called once for every slot that is assigned (in chronological order), including those which
+    /// could not be filled (in which case `slot.human` is `None`). Optimizers use this to track any
+    /// state they need, such as how long each human has been on-call.
     fn update(&mut self, slot: &ScheduleSlot);
 
+    /// Calculates the cost of assigning the `slots_to_fill` in the current rotation to the `candidate`.
+    /// Costs should fall between `0.0` (the ideal candidate) and `1.0` (the worst candidate), and
+    /// `None` should be returned if this optimizer has no preference (in which case it doesn't
+    /// contribute to the candidate's cost at all).
     fn cost(&self, config: &Config, slots_to_fill: &[TimeRange], candidate: &Candidate) -> Option<f64>;
 
+    /// Adds this optimizer's cost to the `candidate`. The default implementation records the result
+    /// of [`Optimizer::cost`] with this optimizer's [`Optimizer::weight`], and it should rarely need
+    /// to be overridden.
     fn populate(&self, config: &Config, slots_to_fill: &[TimeRange], candidate: &mut Candidate) {
         if let Some(cost) = self.cost(config, slots_to_fill, candidate)
         {
@@ -48,10 +74,12 @@ pub trait Optimizer {
     }
 }
 
+/// A human who is able to cover at least one of the slots in a rotation, along with the costs
+/// reported for them by each of the optimizers.
 pub struct Candidate<'a> {
     pub human: &'a str,
     pub available_slots: Vec<bool>,
-    pub factors: HashMap<&'a str, Cost>,
+    pub factors: BTreeMap<&'a str, Cost>,
 }
 
 impl<'a> std::fmt::Debug for Candidate<'a> {
@@ -65,7 +93,7 @@ impl<'a> Candidate<'a> {
         Self {
             human,
             available_slots,
-            factors: HashMap::new(),
+            factors: BTreeMap::new(),
         }
     }
     
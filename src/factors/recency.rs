@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use chrono::{NaiveDateTime};
+use chrono::{DateTime, NaiveDateTime};
 
 use super::{Optimizer, Config, ScheduleSlot, TimeRange, Candidate};
 
@@ -15,7 +15,7 @@ impl Optimizer for Recency {
         Self: Sized {
         let mut recency = HashMap::new();
         for human in config.humans.iter() {
-            recency.insert(human.0.clone(), NaiveDateTime::from_timestamp_opt(0, 0).unwrap());
+            recency.insert(human.0.clone(), DateTime::UNIX_EPOCH.naive_utc());
         }
 
         Box::new(Self { recency })
@@ -36,8 +36,8 @@ impl Optimizer for Recency {
     }
 
     fn cost(&self, _config: &Config, _slots_to_fill: &[TimeRange], candidate: &Candidate) -> Option<f64> {
-        let min = self.recency.values().min().copied().unwrap_or_else(|| NaiveDateTime::from_timestamp_opt(0, 0).unwrap());
-        let max = self.recency.values().max().copied().unwrap_or_else(|| NaiveDateTime::from_timestamp_opt(0, 0).unwrap());
+        let min = self.recency.values().min().copied().unwrap_or(DateTime::UNIX_EPOCH.naive_utc());
+        let max = self.recency.values().max().copied().unwrap_or(DateTime::UNIX_EPOCH.naive_utc());
 
         let range = max - min;
 
@@ -7,6 +7,18 @@ use super::{Optimizer, Config, ScheduleSlot, TimeRange, Candidate};
 
 pub struct Workload {
     workload: HashMap<String, Duration>,
+    capacity: HashMap<String, f64>,
+}
+
+impl Workload {
+    /// The workload of each human, scaled by their capacity so that a part-time human
+    /// is compared against the share of the work they are expected to carry.
+    fn normalized(&self) -> HashMap<&str, f64> {
+        self.workload.iter().map(|(human, workload)| {
+            let capacity = self.capacity.get(human).copied().unwrap_or(1.0);
+            (human.as_str(), workload.num_seconds() as f64 / capacity)
+        }).collect()
+    }
 }
 
 impl Optimizer for Workload {
@@ -14,11 +26,13 @@ impl Optimizer for Workload {
     where
         Self: Sized {
         let mut workload = HashMap::new();
+        let mut capacity = HashMap::new();
         for human in config.humans.iter() {
             workload.insert(human.0.clone(), human.1.prior_workload);
+            capacity.insert(human.0.clone(), human.1.capacity);
         }
 
-        Box::new(Self { workload })
+        Box::new(Self { workload, capacity })
     }
 
     fn name(&self) -> &'static str {
@@ -32,22 +46,50 @@ impl Optimizer for Workload {
     fn update(&mut self, slot: &ScheduleSlot) {
         if let Some(human) = slot.human.as_deref() {
             let workload = self.workload.entry(human.to_string()).or_insert_with(Duration::zero);
-            *workload = *workload + slot.time.len();
+            *workload += slot.time.len();
         }
     }
 
     fn cost(&self, _config: &Config, _slots_to_fill: &[TimeRange], candidate: &Candidate) -> Option<f64> {
-        let min = self.workload.values().min().copied().unwrap_or_else(Duration::zero);
-        let max = self.workload.values().max().copied().unwrap_or_else(Duration::max_value);
+        let normalized = self.normalized();
+
+        let min = normalized.values().copied().fold(f64::INFINITY, f64::min);
+        let max = normalized.values().copied().fold(f64::NEG_INFINITY, f64::max);
 
         let range = max - min;
 
-        if range.is_zero() {
+        if !range.is_finite() || range <= 0.0 {
             return None;
         }
 
-        self.workload.get(candidate.human).map(|&workload| {
-            (workload - min).num_seconds() as f64 / range.num_seconds() as f64
+        normalized.get(candidate.human).map(|&workload| {
+            (workload - min) / range
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::{config::Human, factors::Candidate};
+
+    use super::*;
+
+    #[test]
+    fn test_capacity_scales_workload() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default().with_prior_workload(Duration::hours(30)).with_capacity(0.5))
+            .with_human("bob@example.com", Human::default().with_prior_workload(Duration::hours(40)));
+
+        let workload = Workload::init(&config);
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let slots = [TimeRange::new(start, start + Duration::days(1))];
+
+        let alice = workload.cost(&config, &slots, &Candidate::new("alice@example.com", vec![true]));
+        let bob = workload.cost(&config, &slots, &Candidate::new("bob@example.com", vec![true]));
+
+        assert_eq!(alice, Some(1.0), "alice has done more than their share once their capacity is taken into account");
+        assert_eq!(bob, Some(0.0), "bob has done less than their share once alice's capacity is taken into account");
+    }
+}
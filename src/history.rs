@@ -0,0 +1,140 @@
+use std::{collections::HashMap, path::Path};
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::{config::Config, input, solver::ScheduleSlot, Error};
+
+/// Reads every previously published schedule in the given directory, ordered by their file names.
+/// Files which are not in one of the JSON, YAML or CSV output formats are ignored.
+pub(crate) fn load(dir: &Path) -> Result<Vec<Vec<ScheduleSlot>>, Error> {
+    let io_error = |source| Error::Io { path: dir.to_path_buf(), source };
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+
+        if matches!(extension.as_deref(), Some("json" | "yaml" | "yml" | "csv")) {
+            paths.push(path);
+        }
+    }
+
+    paths.sort();
+    paths.iter().map(|path| input::read_schedule(path)).collect()
+}
+
+/// Calculates the amount of time each human was on-call before `at` in the given schedules. Where
+/// schedules overlap (for example, because a schedule was republished) the last one takes precedence,
+/// ensuring that no period is counted twice.
+///
+/// When a `half_life` is provided, each period is weighted by `0.5 ^ (age / half_life)` where its
+/// age is measured from its midpoint to `at`.
+pub(crate) fn workload(schedules: &[Vec<ScheduleSlot>], at: NaiveDateTime, half_life: Option<Duration>) -> HashMap<String, Duration> {
+    let schedules: Vec<Vec<&ScheduleSlot>> = schedules.iter().map(|schedule| {
+        let mut slots: Vec<&ScheduleSlot> = schedule.iter().filter(|slot| slot.time.start < at).collect();
+        slots.sort_by_key(|slot| slot.time);
+        slots
+    }).collect();
+
+    let mut boundaries: Vec<NaiveDateTime> = schedules.iter().flatten()
+        .flat_map(|slot| [slot.time.start, slot.time.end.min(at)])
+        .collect();
+    boundaries.sort();
+    boundaries.dedup();
+
+    let assignee = |start: NaiveDateTime, end: NaiveDateTime| -> Option<&str> {
+        schedules.iter().rev().find_map(|schedule| {
+            let index = schedule.partition_point(|slot| slot.time.start <= start).checked_sub(1)?;
+            let slot = schedule[index];
+            (slot.time.end >= end).then_some(slot.human.as_deref())
+        }).flatten()
+    };
+
+    let mut workload: HashMap<String, Duration> = HashMap::new();
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let human = match assignee(start, end) {
+            Some(human) => human,
+            None => continue,
+        };
+
+        let weight = match half_life {
+            Some(half_life) if half_life > Duration::zero() => {
+                let age = at - (start + (end - start) / 2);
+                0.5f64.powf(age.num_seconds() as f64 / half_life.num_seconds() as f64)
+            },
+            _ => 1.0,
+        };
+
+        let duration = Duration::seconds(((end - start).num_seconds() as f64 * weight).round() as i64);
+        *workload.entry(human.to_string()).or_insert_with(Duration::zero) += duration;
+    }
+
+    workload
+}
+
+/// Adds the workload from the configuration's history (if any) to each human's prior workload, for
+/// a schedule starting at `at`. The history's path is resolved relative to the `base` directory.
+pub(crate) fn apply(mut config: Config, base: &Path, at: NaiveDateTime) -> Result<Config, Error> {
+    let history = match config.history.as_ref() {
+        Some(history) => history,
+        None => return Ok(config),
+    };
+
+    let schedules = load(&base.join(&history.path))?;
+    for (human, duration) in workload(&schedules, at, history.half_life) {
+        if let Some(info) = config.humans.get_mut(&human) {
+            info.prior_workload += duration;
+        }
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::timerange::TimeRange;
+
+    use super::*;
+
+    fn slot(day: u32, days: i64, human: Option<&str>) -> ScheduleSlot {
+        let start = NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        ScheduleSlot {
+            time: TimeRange::new(start, start + Duration::days(days)),
+            human: human.map(|h| h.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_workload() {
+        let published = vec![
+            slot(2, 2, Some("alice@example.com")),
+            slot(4, 2, Some("bob@example.com")),
+            slot(6, 2, Some("alice@example.com")),
+        ];
+
+        let republished = vec![
+            slot(4, 1, Some("claire@example.com")),
+            slot(5, 1, None),
+        ];
+
+        let workload = workload(&[published, republished], slot(7, 0, None).time.start, None);
+        assert_eq!(workload["alice@example.com"], Duration::days(3), "time after the schedule starts is not counted");
+        assert_eq!(workload.get("bob@example.com"), None, "bob's slot was replaced when the schedule was republished");
+        assert_eq!(workload["claire@example.com"], Duration::days(1));
+    }
+
+    #[test]
+    fn test_workload_decay() {
+        let schedules = vec![vec![
+            slot(1, 2, Some("alice@example.com")),
+            slot(29, 2, Some("bob@example.com")),
+        ]];
+
+        let workload = workload(&schedules, slot(31, 0, None).time.start, Some(Duration::days(29)));
+        assert_eq!(workload["alice@example.com"], Duration::days(1), "alice's slot is one half-life old");
+        assert_eq!(workload["bob@example.com"], Duration::seconds((2.0 * 86400.0 * 0.5f64.powf(1.0 / 29.0)).round() as i64));
+    }
+}
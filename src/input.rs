@@ -0,0 +1,305 @@
+use std::{fmt::Display, path::Path};
+
+use chrono::NaiveDateTime;
+
+use crate::{solver::ScheduleSlot, timerange::TimeRange};
+
+/// The formats in which a previously generated schedule can be read.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Yaml,
+    Csv,
+}
+
+/// A problem encountered while reading a schedule, optionally identifying the line on which it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl InputError {
+    fn new<S: Into<String>>(line: Option<usize>, message: S) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl InputFormat {
+    /// Determines the format of a schedule from its file extension, falling back to inspecting its contents.
+    pub fn detect(path: &Path, contents: &str) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("json") => InputFormat::Json,
+            Some("yaml") | Some("yml") => InputFormat::Yaml,
+            Some("csv") => InputFormat::Csv,
+            _ if contents.trim_start().starts_with(['[', '{']) => InputFormat::Json,
+            _ if contents.trim_start().starts_with("- ") || contents.trim_start().starts_with("schedule:") => InputFormat::Yaml,
+            _ => InputFormat::Csv,
+        }
+    }
+
+    /// Parses a schedule which was written in this format by [`crate::OutputFormat`]. Documents which
+    /// include the schedule alongside its summary are also accepted, in which case the summary is ignored.
+    pub fn parse(self, contents: &str) -> Result<Vec<ScheduleSlot>, InputError> {
+        match self {
+            InputFormat::Json => Self::parse_json(contents),
+            InputFormat::Yaml => Self::parse_yaml(contents),
+            InputFormat::Csv => Self::parse_csv(contents),
+        }
+    }
+
+    fn parse_json(contents: &str) -> Result<Vec<ScheduleSlot>, InputError> {
+        let entries = if contents.trim_start().starts_with('{') {
+            serde_json::from_str::<Report>(contents).map(|report| report.schedule)
+        } else {
+            serde_json::from_str(contents)
+        };
+
+        let entries = entries.map_err(|e| InputError::new(Some(e.line()), format!("{}", e)))?;
+        Ok(Self::unassign(Entry::expand(entries)))
+    }
+
+    fn parse_yaml(contents: &str) -> Result<Vec<ScheduleSlot>, InputError> {
+        let error = |e: serde_yaml::Error| InputError::new(e.location().map(|l| l.line()), format!("{}", e));
+
+        let slots = match serde_yaml::from_str(contents).map_err(error)? {
+            serde_yaml::Value::Mapping(mut report) => report.remove("schedule")
+                .ok_or_else(|| InputError::new(None, "expected a list of slots, or a document with a 'schedule' field"))?,
+            value => value,
+        };
+
+        Ok(Self::unassign(Entry::expand(serde_yaml::from_value(slots).map_err(error)?)))
+    }
+
+    /// Treats slots which were written as UNASSIGNED as not being assigned to anyone.
+    fn unassign(mut slots: Vec<ScheduleSlot>) -> Vec<ScheduleSlot> {
+        for slot in slots.iter_mut() {
+            if slot.human.as_deref() == Some("UNASSIGNED") {
+                slot.human = None;
+            }
+        }
+
+        slots
+    }
+
+    fn parse_csv(contents: &str) -> Result<Vec<ScheduleSlot>, InputError> {
+        let mut records = csv_records(contents).into_iter();
+
+        let (_, header) = records.next().ok_or_else(|| InputError::new(None, "the file is empty, expected a header row"))?;
+        let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| InputError::new(Some(1), format!("the header row is missing the '{}' column", name)));
+
+        let start = column("start")?;
+        let end = column("end")?;
+        let human = column("human")?;
+
+        let mut slots = Vec::new();
+        for (line, record) in records {
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+
+            let field = |index: usize| record.get(index).map(|f| f.trim()).unwrap_or_default();
+
+            let start = parse_datetime(field(start)).ok_or_else(|| InputError::new(Some(line), format!("'{}' is not a valid start time", field(start))))?;
+            let end = parse_datetime(field(end)).ok_or_else(|| InputError::new(Some(line), format!("'{}' is not a valid end time", field(end))))?;
+
+            slots.push(ScheduleSlot {
+                time: TimeRange::new(start, end),
+                human: match field(human) {
+                    "" | "UNASSIGNED" => None,
+                    human => Some(human.to_string()),
+                },
+            });
+        }
+
+        Ok(slots)
+    }
+}
+
+/// A document containing a schedule alongside other information, such as its summary.
+#[derive(serde::Deserialize)]
+struct Report {
+    schedule: Vec<Entry>,
+}
+
+/// An entry in a JSON or YAML schedule, which is either a single slot or a [`crate::Block`] listing the
+/// slots it was merged from.
+#[derive(serde::Deserialize)]
+struct Entry {
+    #[serde(flatten)]
+    time: TimeRange,
+    human: Option<String>,
+    #[serde(default)]
+    slots: Vec<TimeRange>,
+}
+
+impl Entry {
+    /// Converts the entries back into slots, splitting any blocks so that the gaps between their slots are not treated as on-call time.
+    fn expand(entries: Vec<Entry>) -> Vec<ScheduleSlot> {
+        entries.into_iter().flat_map(|entry| {
+            let times = if entry.slots.is_empty() { vec![entry.time] } else { entry.slots };
+            times.into_iter().map(move |time| ScheduleSlot { time, human: entry.human.clone() })
+        }).collect()
+    }
+}
+
+/// Reads a schedule from the given file, detecting its format automatically.
+pub fn read_schedule(path: &Path) -> Result<Vec<ScheduleSlot>, crate::Error> {
+    let contents = std::fs::read_to_string(path).map_err(|source| crate::Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    InputFormat::detect(path, &contents).parse(&contents).map_err(|source| crate::Error::Input {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+/// Splits RFC 4180 formatted CSV into records, returning each alongside the (1-based) line on which it starts.
+fn csv_records(contents: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            },
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {},
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            },
+            '\n' => {
+                field.push(c);
+                line += 1;
+            },
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn slot(day: u32, human: Option<&str>) -> ScheduleSlot {
+        let start = NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_hms_opt(8, 0, 0).unwrap();
+        ScheduleSlot {
+            time: TimeRange::new(start, start + chrono::Duration::hours(8)),
+            human: human.map(|h| h.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let schedule = InputFormat::Csv.parse(concat!(
+            "start,end,human\n",
+            "2023-01-02 08:00:00,2023-01-02 16:00:00,alice@example.com\r\n",
+            "2023-01-03 08:00:00,2023-01-03 16:00:00,\"Doe, Jane\"\n",
+            "\n",
+            "2023-01-04T08:00:00,2023-01-04T16:00:00,UNASSIGNED",
+        )).unwrap();
+
+        assert_eq!(schedule, vec![
+            slot(2, Some("alice@example.com")),
+            slot(3, Some("Doe, Jane")),
+            slot(4, None),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_csv_errors() {
+        assert_eq!(
+            InputFormat::Csv.parse("start,human\n").unwrap_err().to_string(),
+            "line 1: the header row is missing the 'end' column"
+        );
+
+        assert_eq!(
+            InputFormat::Csv.parse("start,end,human\n2023-01-02 08:00:00,tomorrow,alice@example.com\n").unwrap_err().to_string(),
+            "line 2: 'tomorrow' is not a valid end time"
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let schedule = InputFormat::Json.parse(r#"[
+            { "start": "2023-01-02T08:00:00", "end": "2023-01-02T16:00:00", "human": "alice@example.com" },
+            { "start": "2023-01-03T08:00:00", "end": "2023-01-03T16:00:00", "human": null }
+        ]"#).unwrap();
+
+        assert_eq!(schedule, vec![
+            slot(2, Some("alice@example.com")),
+            slot(3, None),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let schedule = InputFormat::Json.parse(r#"{
+            "schedule": [{ "start": "2023-01-02T08:00:00", "end": "2023-01-02T16:00:00", "human": "UNASSIGNED" }],
+            "summary": { "workload": {} }
+        }"#).unwrap();
+        assert_eq!(schedule, vec![slot(2, None)]);
+
+        let schedule = InputFormat::Yaml.parse(concat!(
+            "schedule:\n",
+            "- start: 2023-01-02T08:00:00\n",
+            "  end: 2023-01-02T16:00:00\n",
+            "  human: alice@example.com\n",
+            "summary:\n",
+            "  workload: {}\n",
+        )).unwrap();
+        assert_eq!(schedule, vec![slot(2, Some("alice@example.com"))]);
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let schedule = vec![slot(2, Some("alice@example.com")), slot(3, Some("alice@example.com")), slot(4, None)];
+        let blocks = serde_json::to_string(&crate::Block::merge(&schedule)).unwrap();
+
+        assert_eq!(InputFormat::Json.parse(&blocks).unwrap(), schedule, "blocks should be split back into their slots");
+    }
+}
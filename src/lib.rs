@@ -0,0 +1,59 @@
+//! Automatically compute a reasonable on-call schedule for your team using a declarative,
+//! constraint-based approach.
+//!
+//! ```
+//! use chrono::{Duration, NaiveDate, Utc, Weekday};
+//! use on_call::{Config, Constraint, Human, OutputFormat, Scheduler, Summary};
+//!
+//! let config = Config::new(Duration::days(1))
+//!     .with_constraint(Constraint::DayOfWeek(vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]))
+//!     .with_human("alice@example.com", Human::default())
+//!     .with_human("bob@example.com", Human::default().with_capacity(0.5));
+//!
+//! let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+//! let schedule = Scheduler::new(&config).schedule(start, start + Duration::days(14));
+//!
+//! assert!(schedule.iter().all(|slot| slot.human.is_some()));
+//!
+//! let summary = Summary::from(&schedule).with_adjustments(&config);
+//! println!("{}", summary);
+//!
+//! let mut csv = Vec::new();
+//! OutputFormat::Csv.write(&mut csv, &schedule).unwrap();
+//! assert!(String::from_utf8(csv).unwrap().starts_with("start,end,human"));
+//! ```
+
+#[macro_use]
+mod macros;
+mod agenda;
+mod alternatives;
+mod block;
+mod config;
+mod constraints;
+mod diff;
+mod error;
+mod factors;
+mod history;
+mod input;
+mod output;
+mod solver;
+mod summary;
+mod swap;
+mod timerange;
+mod validation;
+
+pub use agenda::Agenda;
+pub use alternatives::Alternative;
+pub use block::Block;
+pub use config::{Config, Handoff, History, Human};
+pub use constraints::Constraint;
+pub use diff::{Change, Diff, HumanChange};
+pub use error::Error;
+pub use factors::{Candidate, Cost, Optimizer};
+pub use input::{read_schedule, InputError, InputFormat};
+pub use output::{CsvColumn, OutputFormat, OutputOptions, Template, TemplateError};
+pub use solver::{Explanation, Ranking, ScheduleSlot, Scheduler};
+pub use summary::{Fairness, Summary};
+pub use swap::{find_slot, find_swaps, Swap};
+pub use timerange::TimeRange;
+pub use validation::{check_schedule, validate, Location, Problem, Segment, Severity};
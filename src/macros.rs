@@ -1,8 +1,5 @@
 macro_rules! map {
-    ($($key:expr => $value:expr),*) => {{
-        #[allow(unused_mut)]
-        let mut map = ::std::collections::HashMap::new();
-        $(map.insert($key.into(), $value.into());)*
-        map
-    }};
-}
\ No newline at end of file
+    ($($key:expr => $value:expr),*) => {
+        ::std::iter::FromIterator::from_iter([$(($key.into(), $value.into())),*])
+    };
+}
@@ -1,20 +1,18 @@
-use chrono::{Duration, NaiveDate, Utc};
-use clap::Parser;
-use std::path::PathBuf;
-
-mod config;
-mod constraints;
-mod factors;
-#[macro_use]
-mod macros;
-mod output;
-mod solver;
-mod summary;
-mod timerange;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use clap::{Parser, Subcommand};
+use on_call::{check_schedule, find_slot, find_swaps, read_schedule, Agenda, Alternative, Config, CsvColumn, Diff, Error, OutputFormat, OutputOptions, ScheduleSlot, Scheduler, Summary, Template};
+use std::{path::{Path, PathBuf}, process::ExitCode};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
-struct Args {
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The arguments used to select a configuration file and the period of time to schedule.
+#[derive(clap::Args, Debug)]
+struct ScheduleArgs {
     #[arg(short, long)]
     config: PathBuf,
 
@@ -23,60 +21,478 @@ struct Args {
 
     #[arg(short, long)]
     end: Option<NaiveDate>,
+}
 
-    #[arg(short, long, value_enum, default_value = "human")]
-    format: output::OutputFormat,
+impl ScheduleArgs {
+    fn period(&self) -> (NaiveDateTime, NaiveDateTime) {
+        let start = self.start.unwrap_or_else(|| Utc::now().naive_utc().date());
+        let end = self.end.unwrap_or_else(|| start + Duration::days(28));
 
+        (start.and_hms_opt(0, 0, 0).unwrap(), end.and_hms_opt(0, 0, 0).unwrap())
+    }
+}
+
+/// The arguments which control how a schedule is written.
+#[derive(clap::Args, Debug)]
+struct OutputArgs {
+    /// Include the summary alongside the schedule in a single document, rather than printing it separately.
+    #[arg(long)]
+    with_summary: bool,
+
+    /// Split the schedule into a section for each week (used by the markdown format).
     #[arg(long)]
-    debug: bool,
+    group_by_week: bool,
+
+    /// The columns to include in the csv format, separated by commas (defaults to start,end,human).
+    #[arg(long, value_enum, value_delimiter = ',')]
+    columns: Vec<CsvColumn>,
+
+    /// Merge consecutive slots assigned to the same human into on-call blocks (used by the human, json, yaml and csv formats).
+    #[arg(long)]
+    merge: bool,
+
+    /// The template file used to render the schedule by the template format.
+    #[arg(long)]
+    template: Option<PathBuf>,
 }
 
-fn main() {
-    let args = Args::parse();
+impl OutputArgs {
+    fn options(&self) -> Result<OutputOptions, Error> {
+        let mut options = OutputOptions::default().with_columns(self.columns.clone());
+        if self.with_summary {
+            options = options.with_summary();
+        }
 
-    let config: config::Config = {
-        let file = std::fs::File::open(args.config).unwrap();
-        serde_yaml::from_reader(file).unwrap()
-    };
+        if self.group_by_week {
+            options = options.with_weekly_groups();
+        }
+
+        if self.merge {
+            options = options.with_merged_blocks();
+        }
+
+        if let Some(path) = self.template.as_deref() {
+            let source = std::fs::read_to_string(path).map_err(|source| Error::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+            options = options.with_template(Template::parse(&source).map_err(|source| Error::Template {
+                path: path.to_path_buf(),
+                source,
+            })?);
+        }
+
+        Ok(options)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a new schedule from a configuration file.
+    Generate {
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        options: OutputArgs,
+
+        /// Break ties between equally suitable humans using this seed, producing a different (but reproducible) schedule.
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Generate this many alternative schedules using different seeds, and output the fairest of them.
+        #[arg(long, conflicts_with = "seed")]
+        alternatives: Option<u64>,
+
+        #[arg(long, conflicts_with = "alternatives")]
+        debug: bool,
+    },
+
+    /// Validate a configuration file and report any problems without generating a schedule.
+    Validate {
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+    },
+
+    /// Print the summary of an existing schedule (in the JSON, YAML or CSV output formats) without re-solving it.
+    Summarize {
+        /// The schedule to summarize, which may have been edited by hand after it was published.
+        schedule: PathBuf,
+
+        /// The configuration used to apply each human's prior workload and capacity to the summary.
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+
+    /// List the shifts in an existing schedule grouped by human, answering "when am I on-call?".
+    Agenda {
+        /// The published schedule (in the JSON, YAML or CSV output formats).
+        schedule: PathBuf,
+
+        /// The configuration listing the humans to include, even if they have no shifts.
+        #[arg(short, long)]
+        config: PathBuf,
+
+        /// Only list the shifts of this human.
+        #[arg(long)]
+        human: Option<String>,
+
+        /// The time (or date) from which the next shift is found, defaults to now.
+        #[arg(long, value_parser = parse_time)]
+        at: Option<NaiveDateTime>,
+
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+
+    /// Generate a schedule and explain why each rotation was assigned to its human.
+    Explain {
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+
+        /// Break ties between equally suitable humans using this seed, producing a different (but reproducible) schedule.
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+
+    /// Convert an existing schedule (in the JSON, YAML or CSV output formats) into another output format.
+    Export {
+        /// The schedule to export.
+        schedule: PathBuf,
+
+        /// The configuration used to show every human (and when they are unavailable) in the svg format.
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        #[arg(short, long, value_enum, default_value = "csv")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        options: OutputArgs,
+
+        /// The file to write the exported schedule to, defaults to stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Compare two schedules (in the JSON, YAML or CSV output formats) and report the slots whose assignee changed.
+    Diff {
+        /// The original schedule.
+        before: PathBuf,
+
+        /// The updated schedule.
+        after: PathBuf,
+
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+
+    /// Update a published schedule to satisfy the current configuration, only reassigning slots which are no longer valid.
+    Reschedule {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        /// The published schedule (in the JSON, YAML or CSV output formats).
+        #[arg(long)]
+        schedule: PathBuf,
+
+        /// The start date the published schedule was generated from (defaults to the date of its first slot).
+        #[arg(short, long)]
+        start: Option<NaiveDate>,
+
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        options: OutputArgs,
+
+        /// Break ties between equally suitable humans using this seed, producing a different (but reproducible) schedule.
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[arg(long)]
+        debug: bool,
+    },
+
+    /// List the humans who could take one of your slots, along with a slot of theirs you could take in exchange.
+    Swap {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        /// The published schedule (in the JSON, YAML or CSV output formats).
+        #[arg(long)]
+        schedule: PathBuf,
+
+        /// The human who would like to give away one of their slots.
+        #[arg(long)]
+        human: String,
+
+        /// A time (or date) which falls within the slot to be given away.
+        #[arg(long, value_parser = parse_time)]
+        at: NaiveDateTime,
+
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            err.exit_code()
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Generate { schedule, format, options, alternatives: Some(count), .. } => generate_alternatives(&schedule, format, &options, count),
+        Command::Generate { schedule, format, options, seed, debug, .. } => generate(&schedule, format, &options, seed, debug),
+        Command::Validate { schedule } => validate(&schedule),
+        Command::Summarize { schedule, config, format } => summarize(&schedule, config.as_deref(), format),
+        Command::Agenda { schedule, config, human, at, format } => agenda(&schedule, &config, human.as_deref(), at, format),
+        Command::Explain { schedule, seed, format } => explain(&schedule, seed, format),
+        Command::Export { schedule, config, format, options, output } => export(&schedule, config.as_deref(), format, &options, output.as_deref()),
+        Command::Diff { before, after, format } => diff(&before, &after, format),
+        Command::Reschedule { config, schedule, start, format, options, seed, debug } => reschedule(&config, &schedule, start, format, &options, seed, debug),
+        Command::Swap { config, schedule, human, at, format } => swap(&config, &schedule, &human, at, format),
+    }
+}
+
+fn generate(args: &ScheduleArgs, format: OutputFormat, options: &OutputArgs, seed: Option<u64>, debug: bool) -> Result<(), Error> {
+    let (start, end) = args.period();
+    let config = load_valid_config(&args.config, start, end)?;
 
     eprintln!("Humans:");
     for (name, info) in config.humans.iter() {
         eprintln!("  {}: {}", name, info);
     }
 
-    let start = args.start.unwrap_or_else(|| Utc::now().naive_utc().date());
-    let end = args.end.unwrap_or_else(|| start + Duration::days(28));
+    let mut scheduler = Scheduler::new(&config);
+    if let Some(seed) = seed {
+        scheduler = scheduler.with_seed(seed);
+    }
 
-    let mut scheduler = solver::Scheduler::new(&config);
-    if args.debug {
+    if debug {
         scheduler = scheduler.with_debug();
     }
 
-    let schedule = scheduler.schedule(
-        start
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Utc)
-            .unwrap(),
-        end.and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Utc)
-            .unwrap(),
-    );
+    let schedule = scheduler.schedule(start.and_utc(), end.and_utc());
+
+    format.publish(&mut std::io::stdout().lock(), &mut std::io::stderr().lock(), &config, &schedule, options.options()?)
+}
+
+/// Generates several alternative schedules, reporting how they compare before publishing the fairest of them.
+fn generate_alternatives(args: &ScheduleArgs, format: OutputFormat, options: &OutputArgs, count: u64) -> Result<(), Error> {
+    let (start, end) = args.period();
+    let config = load_valid_config(&args.config, start, end)?;
+
+    let alternatives = Alternative::generate(&config, start.and_utc(), end.and_utc(), count);
+
+    eprintln!("Alternatives (fairest first):");
+    OutputFormat::Human.write_alternatives(&mut std::io::stderr().lock(), &alternatives).map_err(Error::Output)?;
+
+    let best = &alternatives[0];
+    match best.seed {
+        Some(seed) => eprintln!("Using the schedule generated with --seed {}", seed),
+        None => eprintln!("Using the schedule generated without a seed"),
+    }
+
+    format.publish(&mut std::io::stdout().lock(), &mut std::io::stderr().lock(), &config, &best.schedule, options.options()?)
+}
+
+fn validate(args: &ScheduleArgs) -> Result<(), Error> {
+    let (config, source) = Config::read(&args.config)?;
+    let (start, end) = args.period();
+
+    let problems = on_call::validate(&config, start, end);
+    if !problems.is_empty() {
+        return Err(Error::Validation {
+            path: args.config.clone(),
+            source,
+            problems,
+        });
+    }
+
+    eprintln!("{}: configuration is valid", args.config.display());
+    Ok(())
+}
+
+fn summarize(schedule_path: &Path, config_path: Option<&Path>, format: OutputFormat) -> Result<(), Error> {
+    let schedule = read_schedule(schedule_path)?;
+    let config = config_path.map(|path| Config::read_at(path, ScheduleSlot::period(&schedule).start)).transpose()?;
+
+    for problem in check_schedule(config.as_ref(), &schedule) {
+        eprintln!("{}: warning: {}", schedule_path.display(), problem);
+    }
+
+    let summary = Summary::from(&schedule);
+    let summary = match config.as_ref() {
+        Some(config) => summary.with_adjustments(config),
+        None => summary,
+    };
+
+    format.write_summary(&mut std::io::stdout().lock(), &summary).map_err(Error::Output)
+}
+
+fn agenda(schedule_path: &Path, config_path: &Path, human: Option<&str>, at: Option<NaiveDateTime>, format: OutputFormat) -> Result<(), Error> {
+    let schedule = read_schedule(schedule_path)?;
+    let config = Config::read_at(config_path, ScheduleSlot::period(&schedule).start)?;
+
+    for problem in check_schedule(Some(&config), &schedule) {
+        eprintln!("{}: warning: {}", schedule_path.display(), problem);
+    }
+
+    let mut agendas = Agenda::for_humans(&config, &schedule, at.unwrap_or_else(|| Utc::now().naive_utc()));
+    if let Some(human) = human {
+        agendas.retain(|agenda| agenda.human == human);
+        if agendas.is_empty() {
+            return Err(Error::UnknownHuman {
+                path: config_path.to_path_buf(),
+                human: human.to_string(),
+            });
+        }
+    }
+
+    format.write_agendas(&mut std::io::stdout().lock(), &agendas).map_err(Error::Output)
+}
+
+fn explain(args: &ScheduleArgs, seed: Option<u64>, format: OutputFormat) -> Result<(), Error> {
+    let (start, end) = args.period();
+    let config = Config::read_at(&args.config, start)?;
+
+    let mut scheduler = Scheduler::new(&config).with_explanations();
+    if let Some(seed) = seed {
+        scheduler = scheduler.with_seed(seed);
+    }
+    scheduler.schedule(start.and_utc(), end.and_utc());
+
+    format.write_explanations(&mut std::io::stdout().lock(), scheduler.explanations()).map_err(Error::Output)
+}
 
-    let summary = summary::Summary::from(&schedule).with_adjustments(&config);
+fn export(schedule_path: &Path, config_path: Option<&Path>, format: OutputFormat, options: &OutputArgs, output: Option<&Path>) -> Result<(), Error> {
+    let schedule = read_schedule(schedule_path)?;
+    let config = config_path.map(|path| Config::read_at(path, ScheduleSlot::period(&schedule).start)).transpose()?;
+
+    let summary = Summary::from(&schedule);
+    let summary = match config.as_ref() {
+        Some(config) => summary.with_adjustments(config),
+        None => summary,
+    };
+
+    let mut output_options = options.options()?;
+    if let Some(config) = config {
+        output_options = output_options.with_config(config);
+    }
+
+    let mut writer: Box<dyn std::io::Write> = match output {
+        Some(path) => Box::new(std::fs::File::create(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    format.write_document(&mut writer, &schedule, &summary, &output_options).map_err(Error::Output)
+}
+
+fn reschedule(config_path: &Path, schedule_path: &Path, start: Option<NaiveDate>, format: OutputFormat, options: &OutputArgs, seed: Option<u64>, debug: bool) -> Result<(), Error> {
+    let published = read_schedule(schedule_path)?;
+    let period = ScheduleSlot::period(&published);
+    let start = start.unwrap_or_else(|| period.start.date()).and_hms_opt(0, 0, 0).unwrap();
+    let config = load_valid_config(config_path, start, period.end)?;
+
+    let mut scheduler = Scheduler::new(&config);
+    if let Some(seed) = seed {
+        scheduler = scheduler.with_seed(seed);
+    }
+
+    if debug {
+        scheduler = scheduler.with_debug();
+    }
+
+    let schedule = scheduler.reschedule(start.and_utc(), &published);
+
+    let changes = Diff::between(&published, &schedule).changes;
+    eprintln!("Reassigned {} period(s) which are no longer valid:", changes.len());
+    for change in changes {
+        eprintln!("  {}: {} -> {}", change.time, change.before.as_deref().unwrap_or("UNASSIGNED"), change.after.as_deref().unwrap_or("UNASSIGNED"));
+    }
+
+    format.publish(&mut std::io::stdout().lock(), &mut std::io::stderr().lock(), &config, &schedule, options.options()?)
+}
+
+fn swap(config_path: &Path, schedule_path: &Path, human: &str, at: NaiveDateTime, format: OutputFormat) -> Result<(), Error> {
+    let schedule = read_schedule(schedule_path)?;
+    let config = Config::read_at(config_path, ScheduleSlot::period(&schedule).start)?;
+
+    let index = find_slot(&schedule, human, at).ok_or_else(|| Error::NotOnCall {
+        path: schedule_path.to_path_buf(),
+        human: human.to_string(),
+        at,
+    })?;
+
+    eprintln!("Humans who could take {} from {}:", schedule[index].time, human);
+
+    let swaps = find_swaps(&config, &schedule, index);
+    format.write_swaps(&mut std::io::stdout().lock(), &swaps).map_err(Error::Output)
+}
+
+fn diff(before: &Path, after: &Path, format: OutputFormat) -> Result<(), Error> {
+    let diff = Diff::between(&read_schedule(before)?, &read_schedule(after)?);
+
+    format.write_diff(&mut std::io::stdout().lock(), &diff).map_err(Error::Output)
+}
+
+/// Parses a point in time provided on the command line, accepting either a date or a date and time.
+fn parse_time(value: &str) -> Result<NaiveDateTime, String> {
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().map(|date| date.and_time(NaiveTime::MIN)))
+        .ok_or_else(|| format!("'{}' is not a valid date or time, expected a value like '2023-01-02' or '2023-01-02 08:00'", value))
+}
+
+/// Loads the configuration file with [`Config::read_valid`], printing any warnings it has.
+fn load_valid_config(path: &Path, start: NaiveDateTime, end: NaiveDateTime) -> Result<Config, Error> {
+    let (config, warnings) = Config::read_valid(path, start, end)?;
+    for warning in warnings {
+        eprintln!("{}", warning);
+    }
+
+    Ok(config)
+}
 
-    eprintln!();
-    eprintln!("{}", summary);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    eprintln!();
-    eprintln!("Schedule:");
+    #[test]
+    fn test_agenda_unknown_human() {
+        let dir = std::env::temp_dir().join(format!("on-call-test-agenda-unknown-human-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let schedule = dir.join("schedule.csv");
+        std::fs::write(&schedule, "start,end,human\n2023-01-02T08:00:00,2023-01-02T16:00:00,alice@example.com\n").unwrap();
 
-    args.format.print(&schedule).unwrap();
+        let result = agenda(&schedule, Path::new("examples/rotation.yaml"), Some("nobody@example.com"), None, OutputFormat::None);
+        std::fs::remove_dir_all(&dir).unwrap();
 
-    if schedule.iter().any(|slot| slot.human.is_none()) {
-        println!();
-        println!("WARNING: There are unassigned slots in the schedule. This is likely due to constraints that are too restrictive.");
-        std::process::exit(1);
+        let err = result.expect_err("an unknown human should be reported as an error");
+        assert!(matches!(&err, Error::UnknownHuman { human, .. } if human == "nobody@example.com"));
+        assert_eq!(err.exit_code(), ExitCode::from(5));
     }
 }
@@ -0,0 +1,136 @@
+use std::{borrow::Cow, io::Write};
+
+use crate::{block::Block, solver::ScheduleSlot};
+
+use super::OutputOptions;
+
+/// A column which can be included when writing a schedule in the CSV format.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvColumn {
+    Start,
+    End,
+    /// The number of hours (possibly fractional) the human is on-call for.
+    Duration,
+    Human,
+    /// The day of the week on which the row starts, like `Mon`.
+    Weekday,
+    /// The timezone of the start and end times, which is always `UTC` since schedules are generated in UTC.
+    Timezone,
+}
+
+impl CsvColumn {
+    /// The columns written when none are chosen explicitly, which can be read back as a schedule.
+    pub fn defaults() -> Vec<Self> {
+        vec![CsvColumn::Start, CsvColumn::End, CsvColumn::Human]
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            CsvColumn::Start => "start",
+            CsvColumn::End => "end",
+            CsvColumn::Duration => "duration",
+            CsvColumn::Human => "human",
+            CsvColumn::Weekday => "weekday",
+            CsvColumn::Timezone => "timezone",
+        }
+    }
+
+    fn value(&self, row: &Block) -> String {
+        match self {
+            CsvColumn::Start => row.time.start.to_string(),
+            CsvColumn::End => row.time.end.to_string(),
+            CsvColumn::Duration => (row.duration.num_minutes() as f64 / 60.0).to_string(),
+            CsvColumn::Human => row.human.as_deref().unwrap_or("UNASSIGNED").to_string(),
+            CsvColumn::Weekday => row.time.start.format("%a").to_string(),
+            CsvColumn::Timezone => "UTC".to_string(),
+        }
+    }
+}
+
+/// Writes the schedule as RFC 4180 CSV with the chosen columns, either with a row for each slot or
+/// for each block of consecutive slots assigned to the same human.
+pub(super) fn write<W: Write>(w: &mut W, schedule: &[ScheduleSlot], options: &OutputOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let columns = if options.columns.is_empty() { CsvColumn::defaults() } else { options.columns.clone() };
+
+    let rows = if options.merge_blocks {
+        Block::merge(schedule)
+    } else {
+        schedule.iter().map(Block::from).collect()
+    };
+
+    writeln!(w, "{}", columns.iter().map(|column| column.name()).collect::<Vec<_>>().join(","))?;
+    for row in rows.iter() {
+        writeln!(w, "{}", columns.iter().map(|column| field(&column.value(row)).into_owned()).collect::<Vec<_>>().join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Quotes a CSV field if it contains any characters which would otherwise change the structure of the file.
+pub(super) fn field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::{input::InputFormat, summary::Summary, timerange::TimeRange};
+
+    use super::*;
+
+    #[test]
+    fn test_csv() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 6).unwrap().and_hms_opt(8, 0, 0).unwrap();
+        let schedule = vec![
+            ScheduleSlot { time: TimeRange::new(start, start + Duration::hours(8)), human: Some("Doe, \"Jane\"".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::hours(8), start + Duration::hours(12)), human: Some("Doe, \"Jane\"".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::hours(12), start + Duration::hours(20)), human: None },
+        ];
+
+        let mut output = Vec::new();
+        write(&mut output, &schedule, &OutputOptions::default()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(InputFormat::Csv.parse(&output).unwrap(), schedule, "the default columns should round-trip through the parser");
+
+        let mut output = Vec::new();
+        let options = OutputOptions::default()
+            .with_columns(vec![CsvColumn::Human, CsvColumn::Weekday, CsvColumn::Duration, CsvColumn::Timezone])
+            .with_merged_blocks();
+        write(&mut output, &schedule, &options).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), concat!(
+            "human,weekday,duration,timezone\n",
+            "\"Doe, \"\"Jane\"\"\",Fri,12,UTC\n",
+            "UNASSIGNED,Fri,8,UTC\n",
+        ));
+    }
+
+    #[test]
+    fn test_csv_merged_round_trip() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 9).unwrap().and_hms_opt(8, 0, 0).unwrap();
+        let schedule: Vec<ScheduleSlot> = (0..5)
+            .flat_map(|day| {
+                let day_start = start + Duration::days(day);
+                vec![
+                    ScheduleSlot { time: TimeRange::new(day_start, day_start + Duration::hours(4)), human: Some("alice@example.com".into()) },
+                    ScheduleSlot { time: TimeRange::new(day_start + Duration::hours(4), day_start + Duration::hours(8)), human: Some("alice@example.com".into()) },
+                ]
+            })
+            .collect();
+
+        let mut output = Vec::new();
+        write(&mut output, &schedule, &OutputOptions::default().with_merged_blocks()).unwrap();
+        let parsed = InputFormat::Csv.parse(&String::from_utf8(output).unwrap()).unwrap();
+
+        assert_eq!(parsed.len(), 5, "only contiguous slots should be merged into a single row");
+        assert_eq!(
+            Summary::from(&parsed).workload("alice@example.com"),
+            Summary::from(&schedule).workload("alice@example.com"),
+            "merged rows should be read back with the same workload as the slots they combine"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::io::Write;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveTime};
+
+use crate::{solver::ScheduleSlot, summary::Summary};
+
+use super::{escape_markup, hours, palette};
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+h2 { margin-top: 2em; }
+.legend span { display: inline-block; margin: 0 1em 0.5em 0; padding: 0.2em 0.6em; border-radius: 4px; }
+table.month { border-collapse: collapse; table-layout: fixed; width: 100%; }
+table.month th { padding: 0.4em; background: #f4f4f4; }
+table.month td { border: 1px solid #ddd; vertical-align: top; height: 6em; padding: 0.2em; }
+table.month td.outside { background: #fafafa; }
+.day { font-size: 0.8em; color: #888; }
+.slot { font-size: 0.75em; margin: 2px 0; padding: 1px 4px; border-radius: 3px; white-space: nowrap; overflow: hidden; text-overflow: ellipsis; cursor: default; }
+.unassigned { background: repeating-linear-gradient(45deg, #f8d7da, #f8d7da 4px, #f1aeb5 4px, #f1aeb5 8px); }
+.chart .row { display: flex; align-items: center; margin: 2px 0; }
+.chart .label { width: 16em; overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+.chart .bar { height: 1.2em; border-radius: 3px; margin-right: 0.5em; }
+table.heatmap td, table.heatmap th { padding: 0.3em 0.6em; text-align: right; }
+"#;
+
+/// Writes a self-contained HTML page showing the schedule as a calendar with a grid for each month,
+/// optionally followed by charts describing its summary.
+pub(super) fn write<W: Write>(w: &mut W, schedule: &[ScheduleSlot], summary: Option<&Summary>) -> Result<(), Box<dyn std::error::Error>> {
+    let adjustments = summary.map(|summary| summary.future_adjustments()).unwrap_or_default();
+    let mut humans: Vec<&str> = schedule.iter().filter_map(|slot| slot.human.as_deref())
+        .chain(adjustments.keys().map(|human| human.as_str()).filter(|human| *human != "UNASSIGNED"))
+        .collect();
+    humans.sort();
+    humans.dedup();
+
+    let colours = palette(&humans);
+    let colour = |human: &str| colours.get(human).cloned().unwrap_or_default();
+
+    start_page(w, "On-Call Schedule")?;
+
+    writeln!(w, "<div class=\"legend\">")?;
+    for human in humans.iter() {
+        writeln!(w, "<span style=\"background: {}\">{}</span>", colour(human), escape_markup(human))?;
+    }
+    if schedule.iter().any(|slot| slot.human.is_none()) {
+        writeln!(w, "<span class=\"unassigned\">UNASSIGNED</span>")?;
+    }
+    writeln!(w, "</div>")?;
+
+    let first = schedule.iter().map(|slot| slot.time.start.date()).min();
+    let last = schedule.iter().map(|slot| (slot.time.end - Duration::seconds(1)).date()).max();
+    if let (Some(first), Some(last)) = (first, last) {
+        let mut month = first.with_day(1).unwrap();
+        while month <= last {
+            write_month(w, month, schedule, &colour)?;
+            month = month + Months::new(1);
+        }
+    }
+
+    if let Some(summary) = summary {
+        write_charts(w, summary, &humans, &colour)?;
+    }
+
+    end_page(w)
+}
+
+/// Writes a self-contained HTML page containing the charts which describe the summary of a schedule.
+pub(super) fn write_summary<W: Write>(w: &mut W, summary: &Summary) -> Result<(), Box<dyn std::error::Error>> {
+    let adjustments = summary.future_adjustments();
+    let mut humans: Vec<&str> = adjustments.keys().map(|human| human.as_str()).filter(|human| *human != "UNASSIGNED").collect();
+    humans.sort();
+
+    let colours = palette(&humans);
+
+    start_page(w, "On-Call Schedule Summary")?;
+    write_charts(w, summary, &humans, &|human| colours.get(human).cloned().unwrap_or_default())?;
+    end_page(w)
+}
+
+/// Writes an HTML table fragment, escaping the contents of each of its cells.
+pub(super) fn table<W: Write, R: IntoIterator<Item = Vec<String>>>(w: &mut W, headers: &[&str], rows: R) -> Result<(), Box<dyn std::error::Error>> {
+    writeln!(w, "<table>")?;
+    writeln!(w, "<tr>{}</tr>", headers.iter().map(|header| format!("<th>{}</th>", escape_markup(header))).collect::<String>())?;
+    for row in rows {
+        writeln!(w, "<tr>{}</tr>", row.iter().map(|cell| format!("<td>{}</td>", escape_markup(cell))).collect::<String>())?;
+    }
+    writeln!(w, "</table>")?;
+
+    Ok(())
+}
+
+fn start_page<W: Write>(w: &mut W, title: &str) -> Result<(), Box<dyn std::error::Error>> {
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html lang=\"en\">")?;
+    writeln!(w, "<head>")?;
+    writeln!(w, "<meta charset=\"utf-8\">")?;
+    writeln!(w, "<title>{}</title>", escape_markup(title))?;
+    writeln!(w, "<style>{}</style>", STYLE)?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+    writeln!(w, "<h1>{}</h1>", escape_markup(title))?;
+
+    Ok(())
+}
+
+fn end_page<W: Write>(w: &mut W) -> Result<(), Box<dyn std::error::Error>> {
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")?;
+
+    Ok(())
+}
+
+fn write_month<W: Write>(w: &mut W, month: NaiveDate, schedule: &[ScheduleSlot], colour: &dyn Fn(&str) -> String) -> Result<(), Box<dyn std::error::Error>> {
+    writeln!(w, "<h2>{}</h2>", month.format("%B %Y"))?;
+    writeln!(w, "<table class=\"month\">")?;
+    writeln!(w, "<tr><th>Mon</th><th>Tue</th><th>Wed</th><th>Thu</th><th>Fri</th><th>Sat</th><th>Sun</th></tr>")?;
+
+    let mut day = month - Duration::days(month.weekday().num_days_from_monday() as i64);
+    while day.month() == month.month() || day < month {
+        writeln!(w, "<tr>")?;
+        for _ in 0..7 {
+            if day.month() != month.month() {
+                writeln!(w, "<td class=\"outside\"></td>")?;
+                day += Duration::days(1);
+                continue;
+            }
+
+            let (start, end) = (day.and_time(NaiveTime::MIN), (day + Duration::days(1)).and_time(NaiveTime::MIN));
+
+            writeln!(w, "<td>")?;
+            writeln!(w, "<div class=\"day\">{}</div>", day.day())?;
+            for slot in schedule.iter().filter(|slot| slot.time.start < end && slot.time.end > start) {
+                let human = slot.human.as_deref().unwrap_or("UNASSIGNED");
+                let details = format!("{} - {}\n{} ({})", slot.time.start, slot.time.end, human, hours(slot.time.len()));
+                let label = format!("{}-{} {}", slot.time.start.max(start).format("%H:%M"), slot.time.end.min(end).format("%H:%M"), human);
+
+                match slot.human.as_deref() {
+                    Some(human) => writeln!(w, "<div class=\"slot\" style=\"background: {}\" title=\"{}\">{}</div>", colour(human), escape_markup(&details), escape_markup(&label))?,
+                    None => writeln!(w, "<div class=\"slot unassigned\" title=\"{}\">{}</div>", escape_markup(&details), escape_markup(&label))?,
+                }
+            }
+            writeln!(w, "</td>")?;
+
+            day += Duration::days(1);
+        }
+        writeln!(w, "</tr>")?;
+    }
+
+    writeln!(w, "</table>")?;
+    Ok(())
+}
+
+fn write_charts<W: Write>(w: &mut W, summary: &Summary, humans: &[&str], colour: &dyn Fn(&str) -> String) -> Result<(), Box<dyn std::error::Error>> {
+    let adjustments = summary.future_adjustments();
+    let fairness = summary.fairness();
+
+    writeln!(w, "<h2>Summary</h2>")?;
+    writeln!(
+        w,
+        "<p>Standard deviation: {:.1} hours, Gini coefficient: {:.3}, max/min ratio: {}</p>",
+        fairness.stddev,
+        fairness.gini,
+        fairness.max_min_ratio.map(|r| format!("{:.2}", r)).unwrap_or_else(|| "n/a".to_string()),
+    )?;
+
+    let chart = |w: &mut W, title: &str, value: &dyn Fn(&str) -> Duration, note: &dyn Fn(&str) -> String| -> Result<(), Box<dyn std::error::Error>> {
+        let peak = humans.iter().map(|human| value(human)).max().unwrap_or_else(Duration::zero).num_seconds().max(1) as f64;
+
+        writeln!(w, "<h3>{}</h3>", title)?;
+        writeln!(w, "<div class=\"chart\">")?;
+        for human in humans {
+            writeln!(
+                w,
+                "<div class=\"row\"><span class=\"label\">{}</span><span class=\"bar\" style=\"width: {:.1}%; background: {}\"></span><span>{}</span></div>",
+                escape_markup(human),
+                value(human).num_seconds() as f64 / peak * 60.0,
+                colour(human),
+                escape_markup(&note(human)),
+            )?;
+        }
+        writeln!(w, "</div>")?;
+        Ok(())
+    };
+
+    chart(w, "Workload", &|human| summary.workload(human), &|human| {
+        format!("{} (future adjustment: {:+}h)", hours(summary.workload(human)), adjustments.get(human).copied().unwrap_or_default())
+    })?;
+    chart(w, "Longest shift", &|human| summary.longest_shift(human), &|human| hours(summary.longest_shift(human)))?;
+
+    let peak = humans.iter().flat_map(|human| summary.weekday_hours(human)).max().unwrap_or_else(Duration::zero).num_seconds().max(1) as f64;
+    writeln!(w, "<h3>Hours by weekday</h3>")?;
+    writeln!(w, "<table class=\"heatmap\">")?;
+    writeln!(w, "<tr><th></th><th>Mon</th><th>Tue</th><th>Wed</th><th>Thu</th><th>Fri</th><th>Sat</th><th>Sun</th></tr>")?;
+    for human in humans {
+        write!(w, "<tr><th>{}</th>", escape_markup(human))?;
+        for hours in summary.weekday_hours(human) {
+            write!(w, "<td style=\"background: rgba(220, 53, 69, {:.2})\">{}</td>", hours.num_seconds() as f64 / peak * 0.8, hours.num_hours())?;
+        }
+        writeln!(w, "</tr>")?;
+    }
+    writeln!(w, "</table>")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timerange::TimeRange;
+
+    use super::*;
+
+    #[test]
+    fn test_html() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 31).unwrap().and_hms_opt(20, 0, 0).unwrap();
+        let schedule = vec![
+            ScheduleSlot { time: TimeRange::new(start, start + Duration::hours(8)), human: Some("<alice>@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::hours(8), start + Duration::hours(16)), human: None },
+        ];
+
+        let mut output = Vec::new();
+        write(&mut output, &schedule, Some(&Summary::from(&schedule))).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("<h2>January 2023</h2>") && output.contains("<h2>February 2023</h2>"), "a slot spanning midnight should appear in both months");
+        assert!(output.contains("title=\"2023-01-31 20:00:00 - 2023-02-01 04:00:00\n&lt;alice&gt;@example.com (8h)\">20:00-00:00 &lt;alice&gt;@example.com</div>"));
+        assert!(output.contains("<div class=\"slot unassigned\""));
+        assert!(output.contains("<h3>Workload</h3>"));
+        assert!(!output.contains("<alice>"), "human names must be escaped");
+    }
+}
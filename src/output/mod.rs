@@ -0,0 +1,715 @@
+use std::{collections::BTreeMap, fmt::Display, io::Write};
+
+use chrono::{Datelike, Duration};
+use serde::Serialize;
+
+mod csv;
+mod html;
+mod svg;
+mod template;
+
+pub use csv::CsvColumn;
+pub use template::{Template, TemplateError};
+
+use crate::{agenda::Agenda, alternatives::Alternative, block::Block, config::Config, diff::Diff, solver::{Explanation, ScheduleSlot}, summary::Summary, swap::Swap, Error};
+
+
+#[derive(clap::ValueEnum, Clone, Debug, Default)]
+pub enum OutputFormat {
+    None,
+    #[default]
+    Human,
+    Json,
+    Yaml,
+    Csv,
+    Markdown,
+    Html,
+    Svg,
+    Template,
+}
+
+/// Options which control how a schedule is written, not every option applies to every format.
+#[derive(Clone, Debug, Default)]
+pub struct OutputOptions {
+    /// Splits the schedule into a separate section for each week (starting on Monday), used by the Markdown format.
+    pub group_by_week: bool,
+    /// The configuration the schedule was generated from, used by the SVG format to show every human and their unavailability.
+    pub config: Option<Config>,
+    /// The columns written by the CSV format, which uses `CsvColumn::defaults()` when empty.
+    pub columns: Vec<CsvColumn>,
+    /// Writes each block of consecutive slots assigned to the same human (rather than each slot), used by the human,
+    /// JSON, YAML and CSV formats. The Markdown, HTML and SVG formats always show blocks.
+    pub merge_blocks: bool,
+    /// The template used to render the schedule (and its summary) by the template format.
+    pub template: Option<Template>,
+    /// Includes the summary alongside the schedule in a single document, used by the human, JSON and YAML formats.
+    /// The Markdown, HTML and template formats always include it.
+    pub include_summary: bool,
+}
+
+impl OutputOptions {
+    pub fn with_weekly_groups(mut self) -> Self {
+        self.group_by_week = true;
+        self
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn with_columns(mut self, columns: Vec<CsvColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_merged_blocks(mut self) -> Self {
+        self.merge_blocks = true;
+        self
+    }
+
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub fn with_summary(mut self) -> Self {
+        self.include_summary = true;
+        self
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::None => write!(f, "none"),
+            OutputFormat::Human => write!(f, "human"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Yaml => write!(f, "yaml"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Markdown => write!(f, "markdown"),
+            OutputFormat::Html => write!(f, "html"),
+            OutputFormat::Svg => write!(f, "svg"),
+            OutputFormat::Template => write!(f, "template"),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Whether this format produces a complete document which should always include the summary of the schedule.
+    pub fn includes_summary(&self) -> bool {
+        matches!(self, OutputFormat::Markdown | OutputFormat::Html | OutputFormat::Template)
+    }
+
+    /// Writes a schedule as a document in this format, which includes its summary when the options ask
+    /// for it or the format always includes it (see [`OutputFormat::includes_summary`]).
+    pub fn write_document<W: Write>(self, w: &mut W, schedule: &[ScheduleSlot], summary: &Summary, options: &OutputOptions) -> Result<(), Box<dyn std::error::Error>> {
+        if options.include_summary || self.includes_summary() {
+            self.write_with_summary(w, schedule, summary, options)
+        } else {
+            self.write_with(w, schedule, options)
+        }
+    }
+
+    /// Publishes a newly generated schedule by writing it to `w` as a document in this format, with its
+    /// summary (adjusted using the configuration) written to `log` beforehand unless it is part of the
+    /// document. Fails once the schedule has been written if any of its slots could not be assigned.
+    pub fn publish<W: Write, L: Write>(self, w: &mut W, log: &mut L, config: &Config, schedule: &[ScheduleSlot], options: OutputOptions) -> Result<(), Error> {
+        let summary = Summary::from(schedule).with_adjustments(config);
+        let options = options.with_config(config.clone());
+
+        if !options.include_summary && !self.includes_summary() {
+            writeln!(log, "\n{}\n\nSchedule:", summary).map_err(|err| Error::Output(err.into()))?;
+        }
+
+        self.write_document(w, schedule, &summary, &options).map_err(Error::Output)?;
+
+        let unassigned: Vec<_> = schedule.iter().filter(|slot| slot.human.is_none()).map(|slot| slot.time).collect();
+        if !unassigned.is_empty() {
+            writeln!(log).map_err(|err| Error::Output(err.into()))?;
+            return Err(Error::Scheduling { unassigned });
+        }
+
+        Ok(())
+    }
+
+    /// Writes the schedule to stdout in this format.
+    pub fn print(self, schedule: &[ScheduleSlot]) -> Result<(), Box<dyn std::error::Error>> {
+        self.write(&mut std::io::stdout().lock(), schedule)
+    }
+
+    /// Writes the schedule to the provided writer in this format.
+    pub fn write<W: Write>(self, w: &mut W, schedule: &[ScheduleSlot]) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_with(w, schedule, &OutputOptions::default())
+    }
+
+    /// Writes the schedule to the provided writer in this format, using the provided options.
+    pub fn write_with<W: Write>(self, w: &mut W, schedule: &[ScheduleSlot], options: &OutputOptions) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            OutputFormat::None => Ok(()),
+            OutputFormat::Human if options.merge_blocks => {
+                Self::write_human_blocks(w, schedule)
+            },
+            OutputFormat::Human => {
+                Self::write_human(w, schedule)
+            },
+            OutputFormat::Json | OutputFormat::Yaml if options.merge_blocks => {
+                self.write_serialized(w, &Block::merge(schedule))
+            },
+            OutputFormat::Json | OutputFormat::Yaml => {
+                self.write_serialized(w, schedule)
+            },
+            OutputFormat::Csv => {
+                csv::write(w, schedule, options)
+            },
+            OutputFormat::Markdown => {
+                Self::write_markdown(w, schedule, options)
+            },
+            OutputFormat::Html => {
+                html::write(w, schedule, None)
+            },
+            OutputFormat::Svg => {
+                svg::write(w, schedule, options.config.as_ref())
+            },
+            OutputFormat::Template => {
+                Self::write_template(w, schedule, &Summary::from(schedule), options)
+            },
+        }
+    }
+
+    fn write_human<W: Write>(w: &mut W, schedule: &[ScheduleSlot]) -> Result<(), Box<dyn std::error::Error>> {
+        for slot in schedule {
+            writeln!(w, "  {}: {}", slot.time, slot.human.as_deref().unwrap_or("UNASSIGNED"))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_template<W: Write>(w: &mut W, schedule: &[ScheduleSlot], summary: &Summary, options: &OutputOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let template = options.template.as_ref().ok_or("the template format requires a template file to be provided with --template")?;
+        write!(w, "{}", template.render(&template::context(schedule, summary)))?;
+
+        Ok(())
+    }
+
+    fn write_human_blocks<W: Write>(w: &mut W, schedule: &[ScheduleSlot]) -> Result<(), Box<dyn std::error::Error>> {
+        for block in Block::merge(schedule) {
+            writeln!(w, "  {}: {} ({} in {} slot(s))", block.time, block.human.as_deref().unwrap_or("UNASSIGNED"), hours(block.duration), block.slots.len())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes any serializable value in this format, used by the machine-readable (JSON and YAML) formats.
+    fn write_serialized<W: Write, T: Serialize + ?Sized>(&self, w: &mut W, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            OutputFormat::Yaml => write!(w, "{}", serde_yaml::to_string(value)?)?,
+            _ => writeln!(w, "{}", serde_json::to_string_pretty(value)?)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_markdown<W: Write>(w: &mut W, schedule: &[ScheduleSlot], options: &OutputOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let blocks = Block::merge(schedule);
+        let row = |block: &Block| vec![
+            block.time.start.format("%Y-%m-%d %H:%M").to_string(),
+            block.time.end.format("%Y-%m-%d %H:%M").to_string(),
+            hours(block.duration),
+            block.human.as_deref().unwrap_or("UNASSIGNED").to_string(),
+        ];
+
+        if !options.group_by_week {
+            return markdown_table(w, &["Start", "End", "Duration", "Human"], blocks.iter().map(row));
+        }
+
+        let week = |block: &Block| block.time.start.date() - Duration::days(block.time.start.weekday().num_days_from_monday() as i64);
+        for (index, block) in blocks.iter().enumerate() {
+            if index > 0 && week(&blocks[index - 1]) == week(block) {
+                continue;
+            }
+
+            if index > 0 {
+                writeln!(w)?;
+            }
+
+            writeln!(w, "### Week of {}", week(block))?;
+            writeln!(w)?;
+
+            let rows = blocks[index..].iter().take_while(|other| week(other) == week(block)).map(row);
+            markdown_table(w, &["Start", "End", "Duration", "Human"], rows)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the differences between two schedules to the provided writer in this format.
+    pub fn write_diff<W: Write>(self, w: &mut W, diff: &Diff) -> Result<(), Box<dyn std::error::Error>> {
+        let human = |h: &Option<String>| h.as_deref().unwrap_or("UNASSIGNED").to_string();
+
+        match self {
+            OutputFormat::None => {},
+            OutputFormat::Svg | OutputFormat::Template => return Err(self.only_schedules()),
+            OutputFormat::Human => {
+                writeln!(w, "Changes:")?;
+                for change in diff.changes.iter() {
+                    writeln!(w, "  {}: {} -> {}", change.time, human(&change.before), human(&change.after))?;
+                }
+
+                if diff.changes.is_empty() {
+                    writeln!(w, "  (none)")?;
+                }
+
+                writeln!(w)?;
+                writeln!(w, "Net hours:")?;
+                for (name, change) in diff.humans.iter() {
+                    writeln!(w, "  {}: {:+} hours ({} -> {})", name, change.net().num_hours(), change.before.num_hours(), change.after.num_hours())?;
+                }
+            },
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(w, diff)?,
+            OutputFormat::Markdown | OutputFormat::Html => {
+                self.table(w, &["Start", "End", "Before", "After"], diff.changes.iter().map(|change| vec![
+                    change.time.start.to_string(),
+                    change.time.end.to_string(),
+                    human(&change.before),
+                    human(&change.after),
+                ]))?;
+
+                writeln!(w)?;
+                self.table(w, &["Human", "Before", "After", "Net"], diff.humans.iter().map(|(name, change)| vec![
+                    name.clone(),
+                    hours(change.before),
+                    hours(change.after),
+                    format!("{:+}h", change.net().num_hours()),
+                ]))?;
+            },
+            OutputFormat::Csv => {
+                writeln!(w, "start,end,before,after")?;
+                for change in diff.changes.iter() {
+                    writeln!(w, "{},{},{},{}", change.time.start, change.time.end, csv::field(&human(&change.before)), csv::field(&human(&change.after)))?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Writes the humans who could take a slot (and what they could give back) to the provided writer in this format.
+    pub fn write_swaps<W: Write>(self, w: &mut W, swaps: &[Swap]) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            OutputFormat::None => {},
+            OutputFormat::Svg | OutputFormat::Template => return Err(self.only_schedules()),
+            OutputFormat::Human => {
+                for (rank, swap) in swaps.iter().enumerate() {
+                    match swap.give_back.as_ref() {
+                        Some(give_back) => writeln!(w, " {}. {} (swapping back {})", rank + 1, swap.human, give_back.time)?,
+                        None => writeln!(w, " {}. {} (no swap back available)", rank + 1, swap.human)?,
+                    }
+
+                    writeln!(w, "    workload stddev: {:+.1} hours, longest shift: {:+} hours", swap.stddev_change, swap.longest_shift_change)?;
+                }
+
+                if swaps.is_empty() {
+                    writeln!(w, "  Nobody is able to take this slot.")?;
+                }
+            },
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(w, swaps)?,
+            OutputFormat::Markdown | OutputFormat::Html => {
+                self.table(w, &["Rank", "Human", "Swap Back", "Workload StdDev", "Longest Shift"], swaps.iter().enumerate().map(|(rank, swap)| vec![
+                    (rank + 1).to_string(),
+                    swap.human.clone(),
+                    swap.give_back.as_ref().map(|s| s.time.to_string()).unwrap_or_default(),
+                    format!("{:+.1}h", swap.stddev_change),
+                    format!("{:+}h", swap.longest_shift_change),
+                ]))?;
+            },
+            OutputFormat::Csv => {
+                writeln!(w, "human,give_back_start,give_back_end,stddev_change,longest_shift_change")?;
+                for swap in swaps {
+                    writeln!(
+                        w,
+                        "{},{},{},{},{}",
+                        csv::field(&swap.human),
+                        swap.give_back.as_ref().map(|s| s.time.start.to_string()).unwrap_or_default(),
+                        swap.give_back.as_ref().map(|s| s.time.end.to_string()).unwrap_or_default(),
+                        swap.stddev_change,
+                        swap.longest_shift_change,
+                    )?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Writes the explanation of how each rotation was assigned to the provided writer in this format.
+    pub fn write_explanations<W: Write>(self, w: &mut W, explanations: &[Explanation]) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            OutputFormat::None => {},
+            OutputFormat::Svg | OutputFormat::Template => return Err(self.only_schedules()),
+            OutputFormat::Human => {
+                for explanation in explanations {
+                    let start = explanation.slots.first().map(|s| s.start).unwrap_or_default();
+                    let end = explanation.slots.last().map(|s| s.end).unwrap_or_default();
+                    writeln!(w, "Rotation {} - {} ({} slot(s)):", start, end, explanation.slots.len())?;
+
+                    for assignment in explanation.assignments.iter() {
+                        writeln!(w, "  {}: {}", assignment.time, assignment.human.as_deref().unwrap_or("UNASSIGNED"))?;
+                    }
+
+                    writeln!(w, "  Candidates:")?;
+                    for (rank, candidate) in explanation.candidates.iter().enumerate() {
+                        let factors: Vec<String> = candidate.factors.iter()
+                            .map(|(name, cost)| format!("{}: {:.3} x{}", name, cost.cost, cost.weight))
+                            .collect();
+
+                        writeln!(w, "   {}. {} = {:.5} ({})", rank + 1, candidate.human, candidate.cost, factors.join(", "))?;
+                    }
+
+                    writeln!(w)?;
+                }
+            },
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(w, explanations)?,
+            OutputFormat::Markdown | OutputFormat::Html => {
+                for explanation in explanations {
+                    let start = explanation.slots.first().map(|s| s.start).unwrap_or_default();
+                    let end = explanation.slots.last().map(|s| s.end).unwrap_or_default();
+                    self.heading(w, &format!("Rotation {} - {}", start, end))?;
+
+                    self.table(w, &["Rank", "Human", "Cost", "Factors"], explanation.candidates.iter().enumerate().map(|(rank, candidate)| vec![
+                        (rank + 1).to_string(),
+                        candidate.human.clone(),
+                        format!("{:.5}", candidate.cost),
+                        candidate.factors.iter().map(|(name, cost)| format!("{}: {:.3} x{}", name, cost.cost, cost.weight)).collect::<Vec<_>>().join(", "),
+                    ]))?;
+
+                    writeln!(w)?;
+                }
+            },
+            OutputFormat::Csv => {
+                writeln!(w, "start,end,human,rank,cost")?;
+                for explanation in explanations {
+                    let start = explanation.slots.first().map(|s| s.start).unwrap_or_default();
+                    let end = explanation.slots.last().map(|s| s.end).unwrap_or_default();
+
+                    for (rank, candidate) in explanation.candidates.iter().enumerate() {
+                        writeln!(w, "{},{},{},{},{:.5}", start, end, csv::field(&candidate.human), rank + 1, candidate.cost)?;
+                    }
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Writes the summary of a schedule to the provided writer in this format.
+    pub fn write_summary<W: Write>(self, w: &mut W, summary: &Summary) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            OutputFormat::None => {},
+            OutputFormat::Svg | OutputFormat::Template => return Err(self.only_schedules()),
+            OutputFormat::Human => write!(w, "{}", summary)?,
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(w, summary)?,
+            OutputFormat::Html => html::write_summary(w, summary)?,
+            OutputFormat::Markdown => {
+                let adjustments = summary.future_adjustments();
+                let mut humans: Vec<&String> = adjustments.keys().collect();
+                humans.sort();
+
+                markdown_table(w, &["Human", "Workload", "Longest Shift", "Shifts", "Weekend", "Night", "Future Adjustment"], humans.into_iter().map(|human| vec![
+                    human.clone(),
+                    hours(summary.workload(human)),
+                    hours(summary.longest_shift(human)),
+                    summary.shift_count(human).to_string(),
+                    hours(summary.weekend_hours(human)),
+                    hours(summary.night_hours(human)),
+                    format!("{:+}h", adjustments[human]),
+                ]))?;
+            },
+            OutputFormat::Csv => {
+                let adjustments = summary.future_adjustments();
+                let mut humans: Vec<&String> = adjustments.keys().collect();
+                humans.sort();
+
+                writeln!(w, "human,workload,longest_shift,future_adjustment")?;
+                for human in humans {
+                    writeln!(
+                        w,
+                        "{},{},{},{}",
+                        csv::field(human),
+                        summary.workload(human).num_seconds() as f64 / 3600.0,
+                        summary.longest_shift(human).num_seconds() as f64 / 3600.0,
+                        adjustments[human],
+                    )?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Writes a schedule together with its summary as a single document, allowing both to be ingested
+    /// at once. The CSV format is unable to represent both and will return an error.
+    pub fn write_with_summary<W: Write>(self, w: &mut W, schedule: &[ScheduleSlot], summary: &Summary, options: &OutputOptions) -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Serialize)]
+        struct Report<'a, T: Serialize + ?Sized> {
+            schedule: &'a T,
+            summary: &'a Summary,
+        }
+
+        match self {
+            OutputFormat::None => Ok(()),
+            OutputFormat::Human => {
+                self.clone().write_summary(w, summary)?;
+                writeln!(w)?;
+                writeln!(w, "Schedule:")?;
+                self.write_with(w, schedule, options)
+            },
+            OutputFormat::Markdown => {
+                self.clone().write_with(w, schedule, options)?;
+                writeln!(w)?;
+                writeln!(w, "### Summary")?;
+                writeln!(w)?;
+                self.write_summary(w, summary)
+            },
+            OutputFormat::Html => html::write(w, schedule, Some(summary)),
+            OutputFormat::Template => Self::write_template(w, schedule, summary, options),
+            OutputFormat::Json | OutputFormat::Yaml if options.merge_blocks => self.write_serialized(w, &Report { schedule: &Block::merge(schedule), summary }),
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(w, &Report { schedule, summary }),
+            OutputFormat::Csv | OutputFormat::Svg => Err(format!("the {} format cannot include the summary, use the summarize command to produce it separately", self).into()),
+        }
+    }
+
+    /// Writes the shifts assigned to each human (grouped by human rather than by time) to the provided writer in this format.
+    pub fn write_agendas<W: Write>(self, w: &mut W, agendas: &[Agenda]) -> Result<(), Box<dyn std::error::Error>> {
+        let next = |agenda: &Agenda| agenda.next.as_ref().map(|next| next.time.to_string()).unwrap_or_else(|| "none".to_string());
+        let gap = |agenda: &Agenda| agenda.longest_gap.map(hours).unwrap_or_else(|| "n/a".to_string());
+
+        match self {
+            OutputFormat::None => {},
+            OutputFormat::Svg | OutputFormat::Template => return Err(self.only_schedules()),
+            OutputFormat::Human => {
+                for agenda in agendas {
+                    writeln!(w, "{}: {} in {} shift(s), next shift: {}, longest gap: {}", agenda.human, hours(agenda.total), agenda.shifts.len(), next(agenda), gap(agenda))?;
+                    for shift in agenda.shifts.iter() {
+                        writeln!(w, "  {} ({})", shift.time, hours(shift.duration))?;
+                    }
+
+                    writeln!(w)?;
+                }
+            },
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(w, agendas)?,
+            OutputFormat::Markdown | OutputFormat::Html => {
+                for agenda in agendas {
+                    self.heading(w, &agenda.human)?;
+                    self.table(w, &["Total", "Shifts", "Next Shift", "Longest Gap"], [vec![
+                        hours(agenda.total),
+                        agenda.shifts.len().to_string(),
+                        next(agenda),
+                        gap(agenda),
+                    ]])?;
+
+                    writeln!(w)?;
+                    self.table(w, &["Start", "End", "Duration"], agenda.shifts.iter().map(|shift| vec![
+                        shift.time.start.format("%Y-%m-%d %H:%M").to_string(),
+                        shift.time.end.format("%Y-%m-%d %H:%M").to_string(),
+                        hours(shift.duration),
+                    ]))?;
+
+                    writeln!(w)?;
+                }
+            },
+            OutputFormat::Csv => {
+                writeln!(w, "human,start,end,duration")?;
+                for agenda in agendas {
+                    for shift in agenda.shifts.iter() {
+                        writeln!(w, "{},{},{},{}", csv::field(&agenda.human), shift.time.start, shift.time.end, shift.duration.num_minutes() as f64 / 60.0)?;
+                    }
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Writes a comparison of alternative schedules (ordered from fairest to least fair) to the provided writer in this format.
+    pub fn write_alternatives<W: Write>(self, w: &mut W, alternatives: &[Alternative]) -> Result<(), Box<dyn std::error::Error>> {
+        let seed = |alternative: &Alternative| alternative.seed.map(|s| s.to_string()).unwrap_or_else(|| "none".to_string());
+        let ratio = |alternative: &Alternative| alternative.fairness.max_min_ratio.map(|r| format!("{:.2}", r)).unwrap_or_default();
+
+        match self {
+            OutputFormat::None => {},
+            OutputFormat::Svg | OutputFormat::Template => return Err(self.only_schedules()),
+            OutputFormat::Human => {
+                writeln!(w, "  rank  seed        unassigned  spread  longest  stddev  gini   max/min")?;
+                for (rank, alternative) in alternatives.iter().enumerate() {
+                    writeln!(
+                        w,
+                        "  {:>4}  {:<10}  {:>10}  {:>6}  {:>7}  {:>6.1}  {:.3}  {:>7}",
+                        rank + 1,
+                        seed(alternative),
+                        alternative.unassigned,
+                        alternative.workload_spread,
+                        alternative.longest_shift,
+                        alternative.fairness.stddev,
+                        alternative.fairness.gini,
+                        ratio(alternative),
+                    )?;
+                }
+            },
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(w, alternatives)?,
+            OutputFormat::Markdown | OutputFormat::Html => {
+                self.table(w, &["Rank", "Seed", "Unassigned", "Spread", "Longest", "Std. Dev.", "Gini", "Max/Min"], alternatives.iter().enumerate().map(|(rank, alternative)| vec![
+                    (rank + 1).to_string(),
+                    seed(alternative),
+                    alternative.unassigned.to_string(),
+                    format!("{}h", alternative.workload_spread),
+                    format!("{}h", alternative.longest_shift),
+                    format!("{:.1}h", alternative.fairness.stddev),
+                    format!("{:.3}", alternative.fairness.gini),
+                    ratio(alternative),
+                ]))?;
+            },
+            OutputFormat::Csv => {
+                writeln!(w, "rank,seed,unassigned,workload_spread,longest_shift,stddev,gini,max_min_ratio")?;
+                for (rank, alternative) in alternatives.iter().enumerate() {
+                    writeln!(
+                        w,
+                        "{},{},{},{},{},{:.3},{:.5},{}",
+                        rank + 1,
+                        alternative.seed.map(|s| s.to_string()).unwrap_or_default(),
+                        alternative.unassigned,
+                        alternative.workload_spread,
+                        alternative.longest_shift,
+                        alternative.fairness.stddev,
+                        alternative.fairness.gini,
+                        ratio(alternative),
+                    )?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// The error returned when a format which can only represent a schedule is used for any other output.
+    fn only_schedules(&self) -> Box<dyn std::error::Error> {
+        format!("the {} format can only be used to write a schedule", self).into()
+    }
+
+    /// Writes a section heading in this format, used by the document (Markdown and HTML) formats to separate their tables.
+    fn heading<W: Write>(&self, w: &mut W, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            OutputFormat::Html => writeln!(w, "<h3>{}</h3>", escape_markup(text))?,
+            _ => writeln!(w, "### {}", text)?,
+        }
+
+        writeln!(w)?;
+        Ok(())
+    }
+
+    /// Writes a table in this format, used by the document (Markdown and HTML) formats to present tabular results.
+    fn table<W: Write, R: IntoIterator<Item = Vec<String>>>(&self, w: &mut W, headers: &[&str], rows: R) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            OutputFormat::Html => html::table(w, headers, rows),
+            _ => markdown_table(w, headers, rows),
+        }
+    }
+}
+
+/// Formats a duration as a (possibly fractional) number of hours, like `40h` or `7.5h`.
+fn hours(duration: Duration) -> String {
+    format!("{}h", duration.num_minutes() as f64 / 60.0)
+}
+
+/// Assigns each human a distinct colour, spacing their hues by the golden angle so that neighbours are easy to tell apart.
+fn palette<'a>(humans: &[&'a str]) -> BTreeMap<&'a str, String> {
+    humans.iter().enumerate()
+        .map(|(index, human)| (*human, format!("hsl({:.0}, 65%, 78%)", (index as f64 * 137.508) % 360.0)))
+        .collect()
+}
+
+/// Escapes the characters which have a special meaning in HTML and SVG text and attributes.
+fn escape_markup(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Writes a Markdown table, escaping any characters in its cells which would break the table.
+fn markdown_table<W: Write, R: IntoIterator<Item = Vec<String>>>(w: &mut W, headers: &[&str], rows: R) -> Result<(), Box<dyn std::error::Error>> {
+    let escape = |cell: &str| cell.replace('|', "\\|").replace('\n', " ");
+
+    writeln!(w, "| {} |", headers.join(" | "))?;
+    writeln!(w, "|{}", " --- |".repeat(headers.len()))?;
+    for row in rows {
+        writeln!(w, "| {} |", row.iter().map(|cell| escape(cell)).collect::<Vec<_>>().join(" | "))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{config::Human, timerange::TimeRange};
+
+    use super::*;
+
+    fn slot(day: u32, start: u32, end: u32, human: &str) -> ScheduleSlot {
+        let date = NaiveDate::from_ymd_opt(2023, 1, day).unwrap();
+        ScheduleSlot {
+            time: TimeRange::new(date.and_hms_opt(start, 0, 0).unwrap(), date.and_hms_opt(end, 0, 0).unwrap()),
+            human: Some(human.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_markdown() {
+        let schedule = vec![
+            slot(6, 8, 12, "alice@example.com"),
+            slot(6, 12, 16, "alice@example.com"),
+            slot(9, 8, 16, "alice@example.com"),
+            slot(10, 8, 12, "Doe | Jane"),
+        ];
+
+        let mut output = Vec::new();
+        OutputFormat::Markdown.write(&mut output, &schedule).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), concat!(
+            "| Start | End | Duration | Human |\n",
+            "| --- | --- | --- | --- |\n",
+            "| 2023-01-06 08:00 | 2023-01-06 16:00 | 8h | alice@example.com |\n",
+            "| 2023-01-09 08:00 | 2023-01-09 16:00 | 8h | alice@example.com |\n",
+            "| 2023-01-10 08:00 | 2023-01-10 12:00 | 4h | Doe \\| Jane |\n",
+        ));
+
+        let mut output = Vec::new();
+        OutputFormat::Markdown.write_with(&mut output, &schedule, &OutputOptions::default().with_weekly_groups()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("### Week of 2023-01-02\n\n| Start |"), "blocks should be grouped by the week in which they start: {}", output);
+        assert!(output.contains("\n### Week of 2023-01-09\n\n| Start | End | Duration | Human |\n| --- | --- | --- | --- |\n| 2023-01-09 08:00"));
+    }
+    #[test]
+    fn test_publish() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default());
+
+        let mut schedule = vec![slot(2, 8, 16, "alice@example.com")];
+
+        let (mut output, mut log) = (Vec::new(), Vec::new());
+        OutputFormat::Json.publish(&mut output, &mut log, &config, &schedule, OutputOptions::default().with_summary()).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("\"summary\""), "the summary should be part of the document");
+        assert!(log.is_empty(), "the summary should not be repeated in the log");
+
+        schedule.push(ScheduleSlot { human: None, ..slot(3, 8, 16, "alice@example.com") });
+
+        let (mut output, mut log) = (Vec::new(), Vec::new());
+        let err = OutputFormat::Csv.publish(&mut output, &mut log, &config, &schedule, OutputOptions::default()).unwrap_err();
+        assert!(matches!(err, Error::Scheduling { ref unassigned } if unassigned.len() == 1));
+        assert_eq!(String::from_utf8(output).unwrap().lines().count(), 3, "the schedule should be written before reporting unassigned slots");
+        assert!(String::from_utf8(log).unwrap().contains("Schedule:"), "the summary should be written to the log");
+    }
+}
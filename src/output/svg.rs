@@ -0,0 +1,132 @@
+use std::{collections::BTreeSet, io::Write};
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+
+use crate::{block::Block, config::Config, constraints::Constraint, solver::ScheduleSlot, timerange::TimeRange};
+
+use super::{escape_markup, hours, palette};
+
+const LABEL_WIDTH: f64 = 220.0;
+const CHART_WIDTH: f64 = 1000.0;
+const HEADER_HEIGHT: f64 = 30.0;
+const ROW_HEIGHT: f64 = 26.0;
+const BAR_HEIGHT: f64 = 18.0;
+
+/// Writes a Gantt style timeline of the schedule as an SVG image, with a row for each human showing their
+/// on-call blocks. When the configuration is available, every human in it receives a row (even if they have
+/// no shifts) and the periods they are unavailable are shaded.
+pub(super) fn write<W: Write>(w: &mut W, schedule: &[ScheduleSlot], config: Option<&Config>) -> Result<(), Box<dyn std::error::Error>> {
+    let humans: Vec<&str> = schedule.iter().filter_map(|slot| slot.human.as_deref())
+        .chain(config.iter().flat_map(|config| config.humans.keys().map(|human| human.as_str())))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let colours = palette(&humans);
+    let blocks = Block::merge(schedule);
+
+    let start = schedule.iter().map(|slot| slot.time.start).min().unwrap_or_default();
+    let end = schedule.iter().map(|slot| slot.time.end).max().unwrap_or_default();
+    let span = (end - start).num_seconds().max(1) as f64;
+    let x = |time: NaiveDateTime| LABEL_WIDTH + (time.clamp(start, end) - start).num_seconds() as f64 / span * CHART_WIDTH;
+    let row = |index: usize| HEADER_HEIGHT + index as f64 * ROW_HEIGHT;
+
+    let width = LABEL_WIDTH + CHART_WIDTH + 10.0;
+    let height = row(humans.len()) + 10.0;
+
+    writeln!(w, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">", width, height, width, height)?;
+    writeln!(w, "<defs><pattern id=\"unavailable\" width=\"6\" height=\"6\" patternUnits=\"userSpaceOnUse\" patternTransform=\"rotate(45)\"><rect width=\"6\" height=\"6\" fill=\"#eee\"/><line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"6\" stroke=\"#bbb\" stroke-width=\"3\"/></pattern></defs>")?;
+    writeln!(w, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>")?;
+
+    // Mark every day on short schedules, and every week (starting on Monday) on longer ones.
+    let weekly = end - start > Duration::days(45);
+    let mut tick = start.date().and_time(NaiveTime::MIN);
+    while tick < end {
+        if tick >= start && (!weekly || tick.weekday().num_days_from_monday() == 0) {
+            writeln!(w, "<line x1=\"{:.1}\" y1=\"{}\" x2=\"{:.1}\" y2=\"{}\" stroke=\"#ddd\"/>", x(tick), HEADER_HEIGHT - 5.0, x(tick), row(humans.len()))?;
+            writeln!(w, "<text x=\"{:.1}\" y=\"{}\" fill=\"#666\">{}</text>", x(tick) + 2.0, HEADER_HEIGHT - 10.0, tick.format("%b %d"))?;
+        }
+
+        tick += Duration::days(1);
+    }
+
+    for (index, human) in humans.iter().enumerate() {
+        let top = row(index);
+        writeln!(w, "<text x=\"5\" y=\"{:.1}\">{}</text>", top + ROW_HEIGHT / 2.0 + 4.0, escape_markup(human))?;
+        writeln!(w, "<line x1=\"0\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#eee\"/>", top + ROW_HEIGHT, width, top + ROW_HEIGHT)?;
+
+        let constraints = config.and_then(|config| config.humans.get(*human)).map(|human| human.constraints.as_slice()).unwrap_or_default();
+        for constraint in constraints {
+            if let Constraint::Unavailable { start: from, end: until } = constraint {
+                let period = TimeRange::new(from.and_time(NaiveTime::MIN), until.and_time(NaiveTime::MIN));
+                if period.end <= start || period.start >= end {
+                    continue;
+                }
+
+                writeln!(
+                    w,
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\" fill=\"url(#unavailable)\"><title>{}</title></rect>",
+                    x(period.start), top, x(period.end) - x(period.start), ROW_HEIGHT, escape_markup(&format!("{} is {}", human, constraint)),
+                )?;
+            }
+        }
+
+        for block in blocks.iter().filter(|block| block.human.as_deref() == Some(*human)) {
+            writeln!(
+                w,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\" rx=\"3\" fill=\"{}\" stroke=\"#555\" stroke-width=\"0.5\"><title>{}</title></rect>",
+                x(block.time.start), top + (ROW_HEIGHT - BAR_HEIGHT) / 2.0, (x(block.time.end) - x(block.time.start)).max(1.0), BAR_HEIGHT,
+                colours[human], escape_markup(&format!("{}\n{} - {} ({})", human, block.time.start, block.time.end, hours(block.duration))),
+            )?;
+        }
+    }
+
+    for slot in schedule.iter().filter(|slot| slot.human.is_none()) {
+        writeln!(
+            w,
+            "<rect x=\"{:.1}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"#dc3545\" fill-opacity=\"0.6\"><title>UNASSIGNED\n{} ({})</title></rect>",
+            x(slot.time.start), HEADER_HEIGHT - 5.0, (x(slot.time.end) - x(slot.time.start)).max(2.0), row(humans.len()) - HEADER_HEIGHT + 5.0,
+            slot.time, hours(slot.time.len()),
+        )?;
+    }
+
+    writeln!(w, "</svg>")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::config::Human;
+
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default().with_constraints(vec![Constraint::Unavailable {
+                start: NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(),
+                end: NaiveDate::from_ymd_opt(2023, 1, 4).unwrap(),
+            }]))
+            .with_human("<claire>", Human::default());
+
+        let schedule = vec![
+            ScheduleSlot { time: TimeRange::new(start, start + Duration::days(1)), human: Some("alice@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::days(1), start + Duration::days(2)), human: Some("alice@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::days(2), start + Duration::days(4)), human: None },
+        ];
+
+        let mut output = Vec::new();
+        write(&mut output, &schedule, Some(&config)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("<svg "));
+        assert!(output.contains(">&lt;claire&gt;</text>"), "humans without shifts should still have an (escaped) row");
+        assert_eq!(output.matches("rx=\"3\"").count(), 1, "consecutive slots should be merged into a single bar");
+        assert!(output.contains("x=\"470.0\" y=\"82.0\" width=\"250.0\" height=\"26\" fill=\"url(#unavailable)\""), "bob's unavailability should be shaded in their row");
+        assert!(output.contains("x=\"720.0\" y=\"25\" width=\"500.0\""), "the unassigned slot should be marked across every row");
+    }
+}
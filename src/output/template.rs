@@ -0,0 +1,427 @@
+use std::{borrow::Cow, fmt::Display};
+
+use chrono::{format::{Item, StrftimeItems}, NaiveDateTime};
+use serde_json::{json, Value};
+
+use crate::{block::Block, solver::ScheduleSlot, summary::Summary};
+
+/// A user supplied template, written in a Handlebars-like syntax, which is used to render the schedule
+/// in a custom format (like a Slack announcement). The following tags are supported:
+///
+///  - `{{path}}` writes a value from the context, like `{{human}}` or `{{fairness.gini}}`.
+///  - `{{date path "%a %d %b"}}` writes a time from the context using the given `strftime` format.
+///  - `{{round path 2}}` writes a number from the context rounded to the given number of decimal places.
+///  - `{{#each path}}...{{/each}}` repeats its contents for each item in a list (or each value in a map), where
+///    `{{this}}`, `{{@index}}`, `{{@first}}`, `{{@last}}` and `{{@key}}` describe the current item.
+///  - `{{#if path}}...{{else}}...{{/if}}` (and `{{#unless path}}`) include their contents based on whether a value is
+///    present and not `false`, `0` or empty.
+///  - `{{! comment }}` is ignored.
+///
+/// Values which are not found in the current item are looked up in the items which enclose it, and `../path`
+/// refers explicitly to the enclosing item. Tags which are alone on their line do not leave a blank line behind.
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// A problem with the syntax of a template, along with the (1-based) line on which it was found.
+#[derive(Debug)]
+pub struct TemplateError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl TemplateError {
+    fn new<S: Into<String>>(line: usize, message: S) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Value(Expression),
+    Each { path: String, body: Vec<Node> },
+    If { path: String, negate: bool, then: Vec<Node>, otherwise: Vec<Node> },
+}
+
+#[derive(Debug, Clone)]
+enum Expression {
+    Path(String),
+    Date { path: String, format: String },
+    Round { path: String, places: usize },
+}
+
+enum Token {
+    Text(String),
+    Tag(usize, Tag),
+}
+
+enum Tag {
+    Open { kind: &'static str, path: String },
+    Else,
+    Close(String),
+    Value(Expression),
+    Comment,
+}
+
+/// The item currently being rendered, along with its position when it is part of an `{{#each}}` block.
+struct Frame<'a> {
+    value: &'a Value,
+    index: usize,
+    len: usize,
+    key: Option<&'a str>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut tokens = Vec::new();
+        for (index, line) in source.split_inclusive('\n').enumerate() {
+            tokenize(line, index + 1, &mut tokens)?;
+        }
+
+        let (nodes, _) = parse_nodes(&mut tokens.into_iter(), None)?;
+        Ok(Self { nodes })
+    }
+
+    /// Renders the template using the values in the provided context.
+    pub fn render(&self, context: &Value) -> String {
+        let mut output = String::new();
+        render_nodes(&self.nodes, &mut vec![Frame { value: context, index: 0, len: 1, key: None }], &mut output);
+        output
+    }
+}
+
+/// Splits a line of the template into text and tags. Lines which only contain a block tag (or a comment) are
+/// reduced to the tag itself, so that they don't leave blank lines in the output.
+fn tokenize(line: &str, number: usize, tokens: &mut Vec<Token>) -> Result<(), TemplateError> {
+    let trimmed = line.trim();
+    if trimmed.starts_with("{{") && trimmed.ends_with("}}") && trimmed.matches("{{").count() == 1 {
+        let tag = parse_tag(&trimmed[2..trimmed.len() - 2], number)?;
+        if !matches!(tag, Tag::Value(_)) {
+            tokens.push(Token::Tag(number, tag));
+            return Ok(());
+        }
+    }
+
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+
+        let end = rest[start..].find("}}").ok_or_else(|| TemplateError::new(number, "this tag is not closed with '}}' (tags must not span multiple lines)"))?;
+        tokens.push(Token::Tag(number, parse_tag(&rest[start + 2..start + end], number)?));
+        rest = &rest[start + end + 2..];
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+
+    Ok(())
+}
+
+fn parse_tag(content: &str, line: usize) -> Result<Tag, TemplateError> {
+    let content = content.trim();
+    if content.starts_with('!') {
+        return Ok(Tag::Comment);
+    }
+
+    if content == "else" {
+        return Ok(Tag::Else);
+    }
+
+    if let Some(name) = content.strip_prefix('/') {
+        return Ok(Tag::Close(name.trim().to_string()));
+    }
+
+    let arguments = split_arguments(content.strip_prefix('#').unwrap_or(content), line)?;
+    if let Some(block) = content.strip_prefix('#') {
+        let kind = match arguments.first().map(|a| a.as_str()) {
+            Some("each") => "each",
+            Some("if") => "if",
+            Some("unless") => "unless",
+            _ => return Err(TemplateError::new(line, format!("'{{{{#{}}}}}' is not a supported block, expected #each, #if or #unless", block.trim()))),
+        };
+
+        return match arguments.as_slice() {
+            [_, path] => Ok(Tag::Open { kind, path: path.clone() }),
+            _ => Err(TemplateError::new(line, format!("the #{} block expects a single value, like '{{{{#{} blocks}}}}'", kind, kind))),
+        };
+    }
+
+    match arguments.as_slice() {
+        [path] => Ok(Tag::Value(Expression::Path(path.clone()))),
+        [helper, path, format] if helper == "date" => {
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                return Err(TemplateError::new(line, format!("'{}' is not a valid date format", format)));
+            }
+
+            Ok(Tag::Value(Expression::Date { path: path.clone(), format: format.clone() }))
+        },
+        [helper, path, places] if helper == "round" && places.parse::<usize>().is_ok() => {
+            Ok(Tag::Value(Expression::Round { path: path.clone(), places: places.parse().unwrap() }))
+        },
+        [helper, ..] if helper == "date" => Err(TemplateError::new(line, "the date helper expects a value and a format, like '{{date start \"%a %d %b\"}}'")),
+        [helper, ..] if helper == "round" => Err(TemplateError::new(line, "the round helper expects a value and a number of decimal places, like '{{round hours 1}}'")),
+        [helper, ..] => Err(TemplateError::new(line, format!("'{}' is not a supported helper, only 'date' and 'round' are available", helper))),
+        [] => Err(TemplateError::new(line, "this tag is empty")),
+    }
+}
+
+/// Splits the contents of a tag on whitespace, keeping quoted arguments together.
+fn split_arguments(content: &str, line: usize) -> Result<Vec<String>, TemplateError> {
+    let mut arguments = Vec::new();
+    let mut rest = content.trim_start();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').ok_or_else(|| TemplateError::new(line, "this tag contains an unterminated string"))?;
+            arguments.push(quoted[..end].to_string());
+            rest = quoted[end + 1..].trim_start();
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            arguments.push(rest[..end].to_string());
+            rest = rest[end..].trim_start();
+        }
+    }
+
+    Ok(arguments)
+}
+
+/// Parses tokens until the end of the enclosing block (if any), returning its contents and whether
+/// it was ended by an `{{else}}` tag.
+fn parse_nodes(tokens: &mut std::vec::IntoIter<Token>, block: Option<(&str, usize, bool)>) -> Result<(Vec<Node>, bool), TemplateError> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Tag(_, Tag::Comment) => {},
+            Token::Tag(_, Tag::Value(expression)) => nodes.push(Node::Value(expression)),
+            Token::Tag(line, Tag::Open { kind: "each", path }) => {
+                let (body, _) = parse_nodes(tokens, Some(("each", line, false)))?;
+                nodes.push(Node::Each { path, body });
+            },
+            Token::Tag(line, Tag::Open { kind, path }) => {
+                let (then, has_else) = parse_nodes(tokens, Some((kind, line, true)))?;
+                let otherwise = if has_else { parse_nodes(tokens, Some((kind, line, false)))?.0 } else { Vec::new() };
+                nodes.push(Node::If { path, negate: kind == "unless", then, otherwise });
+            },
+            Token::Tag(_, Tag::Else) if matches!(block, Some((_, _, true))) => return Ok((nodes, true)),
+            Token::Tag(line, Tag::Else) => return Err(TemplateError::new(line, "'{{else}}' can only be used once inside an #if or #unless block")),
+            Token::Tag(_, Tag::Close(name)) if block.map(|(kind, _, _)| kind) == Some(name.as_str()) => return Ok((nodes, false)),
+            Token::Tag(line, Tag::Close(name)) => return Err(match block {
+                Some((kind, opened, _)) => TemplateError::new(line, format!("expected '{{{{/{}}}}}' to close the block opened on line {}, but found '{{{{/{}}}}}'", kind, opened, name)),
+                None => TemplateError::new(line, format!("'{{{{/{}}}}}' does not close any block", name)),
+            }),
+        }
+    }
+
+    match block {
+        Some((kind, line, _)) => Err(TemplateError::new(line, format!("the #{} block is never closed with '{{{{/{}}}}}'", kind, kind))),
+        None => Ok((nodes, false)),
+    }
+}
+
+fn render_nodes<'a>(nodes: &[Node], frames: &mut Vec<Frame<'a>>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Value(Expression::Path(path)) => {
+                if let Some(value) = lookup(frames, path) {
+                    output.push_str(&to_text(&value));
+                }
+            },
+            Node::Value(Expression::Date { path, format }) => {
+                if let Some(value) = lookup(frames, path) {
+                    let text = to_text(&value);
+                    match ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"].iter().find_map(|f| NaiveDateTime::parse_from_str(&text, f).ok()) {
+                        Some(time) => output.push_str(&time.format(format).to_string()),
+                        None => output.push_str(&text),
+                    }
+                }
+            },
+            Node::Value(Expression::Round { path, places }) => {
+                match lookup(frames, path).as_deref() {
+                    Some(Value::Number(number)) => output.push_str(&format!("{:.*}", places, number.as_f64().unwrap_or_default())),
+                    Some(value) => output.push_str(&to_text(value)),
+                    None => {},
+                }
+            },
+            Node::Each { path, body } => {
+                let items: Vec<(Option<&str>, &Value)> = match lookup(frames, path) {
+                    Some(Cow::Borrowed(Value::Array(items))) => items.iter().map(|item| (None, item)).collect(),
+                    Some(Cow::Borrowed(Value::Object(items))) => items.iter().map(|(key, item)| (Some(key.as_str()), item)).collect(),
+                    _ => Vec::new(),
+                };
+
+                let len = items.len();
+                for (index, (key, value)) in items.into_iter().enumerate() {
+                    frames.push(Frame { value, index, len, key });
+                    render_nodes(body, frames, output);
+                    frames.pop();
+                }
+            },
+            Node::If { path, negate, then, otherwise } => {
+                let truthy = lookup(frames, path).map(|value| is_truthy(&value)).unwrap_or(false);
+                render_nodes(if truthy != *negate { then } else { otherwise }, frames, output);
+            },
+        }
+    }
+}
+
+/// Finds a value in the current item (or the items which enclose it), following a dotted path.
+fn lookup<'a>(frames: &[Frame<'a>], path: &str) -> Option<Cow<'a, Value>> {
+    let mut frames = frames;
+    let mut path = path;
+    while let Some(rest) = path.strip_prefix("../") {
+        frames = &frames[..frames.len().saturating_sub(1).max(1)];
+        path = rest;
+    }
+
+    let frame = frames.last()?;
+    match path {
+        "this" | "." => return Some(Cow::Borrowed(frame.value)),
+        "@index" => return Some(Cow::Owned(json!(frame.index))),
+        "@first" => return Some(Cow::Owned(json!(frame.index == 0))),
+        "@last" => return Some(Cow::Owned(json!(frame.index + 1 == frame.len))),
+        "@key" => return frame.key.map(|key| Cow::Owned(json!(key))),
+        _ => {},
+    }
+
+    let path = path.strip_prefix("this.").unwrap_or(path);
+    let mut segments = path.split('.');
+    let first = segments.next()?;
+
+    let mut value = frames.iter().rev().find_map(|frame| frame.value.get(first))?;
+    for segment in segments {
+        value = match value {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            value => value.get(segment)?,
+        };
+    }
+
+    Some(Cow::Borrowed(value))
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.as_f64().map(|n| n.to_string()).unwrap_or_else(|| number.to_string()),
+        value => value.to_string(),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64() != Some(0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(items) => !items.is_empty(),
+    }
+}
+
+/// Builds the values which are made available to templates from the schedule and its summary.
+pub(super) fn context(schedule: &[ScheduleSlot], summary: &Summary) -> Value {
+    let hours = |d: chrono::Duration| d.num_seconds() as f64 / 3600.0;
+    let human = |human: &Option<String>| human.as_deref().unwrap_or("UNASSIGNED").to_string();
+
+    let adjustments = summary.future_adjustments();
+    let mut humans: Vec<&String> = adjustments.keys().filter(|human| *human != "UNASSIGNED").collect();
+    humans.sort();
+
+    json!({
+        "start": schedule.iter().map(|slot| slot.time.start).min().map(|start| start.to_string()),
+        "end": schedule.iter().map(|slot| slot.time.end).max().map(|end| end.to_string()),
+        "unassigned": schedule.iter().filter(|slot| slot.human.is_none()).count(),
+        "slots": schedule.iter().map(|slot| json!({
+            "start": slot.time.start.to_string(),
+            "end": slot.time.end.to_string(),
+            "hours": hours(slot.time.len()),
+            "human": human(&slot.human),
+            "unassigned": slot.human.is_none(),
+        })).collect::<Vec<_>>(),
+        "blocks": Block::merge(schedule).iter().map(|block| json!({
+            "start": block.time.start.to_string(),
+            "end": block.time.end.to_string(),
+            "hours": hours(block.duration),
+            "human": human(&block.human),
+            "unassigned": block.human.is_none(),
+            "slots": block.slots.len(),
+        })).collect::<Vec<_>>(),
+        "humans": humans.iter().map(|name| json!({
+            "name": name,
+            "hours": hours(summary.workload(name)),
+            "shifts": summary.shift_count(name),
+            "blocks": summary.block_count(name),
+            "longestShift": hours(summary.longest_shift(name)),
+            "weekendHours": hours(summary.weekend_hours(name)),
+            "nightHours": hours(summary.night_hours(name)),
+            "futureAdjustment": adjustments[*name],
+        })).collect::<Vec<_>>(),
+        "fairness": summary.fairness(),
+        "summary": summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let template = Template::parse(concat!(
+            "{{! announcement }}\n",
+            "On-call from {{date start \"%a %d %b\"}}:\n",
+            "{{#each blocks}}\n",
+            "{{#if unassigned}}\n",
+            "{{@index}}. nobody ({{../team}})\n",
+            "{{else}}\n",
+            "{{@index}}. {{human}} for {{round hours 1}}h{{#unless @last}},{{/unless}}\n",
+            "{{/if}}\n",
+            "{{/each}}\n",
+        )).unwrap();
+
+        let context = json!({
+            "team": "SRE",
+            "start": "2023-01-02 08:00:00",
+            "blocks": [
+                { "human": "alice@example.com", "hours": 40.0, "unassigned": false },
+                { "human": "UNASSIGNED", "hours": 7.5, "unassigned": true },
+                { "human": "bob@example.com", "hours": 7.5, "unassigned": false },
+            ],
+        });
+
+        assert_eq!(template.render(&context), concat!(
+            "On-call from Mon 02 Jan:\n",
+            "0. alice@example.com for 40.0h,\n",
+            "1. nobody (SRE)\n",
+            "2. bob@example.com for 7.5h\n",
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |source: &str| Template::parse(source).unwrap_err().to_string();
+
+        assert_eq!(error("{{#each blocks}}\n{{human}}\n"), "line 1: the #each block is never closed with '{{/each}}'");
+        assert_eq!(error("{{#if human}}\n{{/each}}\n"), "line 2: expected '{{/if}}' to close the block opened on line 1, but found '{{/each}}'");
+        assert_eq!(error("Hello {{name"), "line 1: this tag is not closed with '}}' (tags must not span multiple lines)");
+        assert_eq!(error("{{upper name}}"), "line 1: 'upper' is not a supported helper, only 'date' and 'round' are available");
+        assert_eq!(error("{{date start \"%Q\"}}"), "line 1: '%Q' is not a valid date format");
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
-    config::{Config, Human},
-    factors::{self, Candidate, Optimizer},
+    config::Config,
+    factors::{self, Candidate, Cost, Optimizer},
     timerange::TimeRange,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -13,10 +14,41 @@ pub struct ScheduleSlot {
     pub human: Option<String>,
 }
 
+impl ScheduleSlot {
+    /// The period covered by a schedule, from the start of its earliest slot to the end of its latest
+    /// (which is a zero length period at the epoch for an empty schedule).
+    pub fn period(schedule: &[ScheduleSlot]) -> TimeRange {
+        TimeRange::new(
+            schedule.iter().map(|slot| slot.time.start).min().unwrap_or_default(),
+            schedule.iter().map(|slot| slot.time.end).max().unwrap_or_default(),
+        )
+    }
+}
+
+/// The reasoning behind the assignment of a single rotation, which is recorded when
+/// [`Scheduler::with_explanations`] is used.
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub slots: Vec<TimeRange>,
+    /// The humans who were able to cover at least one of the slots, ordered by their cost (lowest first).
+    pub candidates: Vec<Ranking>,
+    pub assignments: Vec<ScheduleSlot>,
+}
+
+/// The total cost of a candidate for a rotation, along with the cost reported by each factor.
+#[derive(Debug, Clone, Serialize)]
+pub struct Ranking {
+    pub human: String,
+    pub cost: f64,
+    pub factors: BTreeMap<String, Cost>,
+}
+
 pub struct Scheduler<'a> {
     config: &'a Config,
     factors: Vec<Box<dyn Optimizer>>,
     debug: bool,
+    seed: Option<u64>,
+    explanations: Option<Vec<Explanation>>,
 }
 
 impl<'a> Scheduler<'a> {
@@ -27,6 +59,8 @@ impl<'a> Scheduler<'a> {
             config,
             factors,
             debug: false,
+            seed: None,
+            explanations: None,
         }
     }
 
@@ -37,19 +71,54 @@ impl<'a> Scheduler<'a> {
         }
     }
 
+    /// Breaks ties between candidates with equal costs using a pseudo-random order derived from the
+    /// `seed`, rather than by their names. Different seeds yield different schedules, while the same
+    /// configuration and seed will always produce the same schedule.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    /// Records an [`Explanation`] for every rotation which is scheduled, these can be retrieved
+    /// using [`Scheduler::explanations`].
+    pub fn with_explanations(self) -> Self {
+        Self {
+            explanations: Some(Vec::new()),
+            ..self
+        }
+    }
+
+    /// The explanations recorded for each rotation, if [`Scheduler::with_explanations`] was used.
+    pub fn explanations(&self) -> &[Explanation] {
+        self.explanations.as_deref().unwrap_or_default()
+    }
+
+    /// Registers an additional optimizer which will contribute to the cost of each candidate.
+    pub fn with_optimizer(mut self, optimizer: Box<dyn Optimizer>) -> Self {
+        self.factors.push(optimizer);
+        self
+    }
+
+    /// Removes the optimizer with the given name, allowing the built-in optimizers (`coverage`,
+    /// `length`, `recency` and `workload`) to be disabled or replaced.
+    pub fn without_optimizer(mut self, name: &str) -> Self {
+        self.factors.retain(|factor| factor.name() != name);
+        self
+    }
+
     pub fn schedule(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<ScheduleSlot> {
         let mut slots = Vec::new();
 
         // TODO: In future, accept a weights input to allow iterative scheduling while remaining fair
 
-        let initial_range: Box<dyn Iterator<Item = TimeRange>> = Box::new(vec![TimeRange::new(start.naive_utc(), end.naive_utc())].into_iter());
+        let slots_to_fill = self.config.slots(start.naive_utc(), end.naive_utc());
 
-        let slots_to_fill = self.config.constraints.iter().fold(initial_range, |ranges, constraint| {
-            constraint.flat_map(ranges)
-        }).collect::<Vec<_>>();
+        let origin = self.config.handoff.as_ref().map(|handoff| handoff.origin()).unwrap_or_else(|| start.naive_utc());
 
-        for slot in slots_to_fill.chunks(self.config.shift_length.abs().num_days() as usize) {
-            let mut rotation_assignments = self.schedule_rotation(slot);
+        for slot in Self::shifts(origin, self.config.shift_length, slots_to_fill) {
+            let mut rotation_assignments = self.schedule_rotation(&slot);
 
             for assignment in rotation_assignments.iter() {
                 for factor in self.factors.iter_mut() {
@@ -63,21 +132,138 @@ impl<'a> Scheduler<'a> {
         slots
     }
 
-    fn schedule_rotation(&self, slots_to_fill: &[TimeRange]) -> Vec<ScheduleSlot> {
+    /// Updates a previously published schedule to satisfy the current configuration while causing
+    /// as little disruption as possible. Slots which are no longer required by the configuration's
+    /// constraints are dropped and every assignment which remains valid is kept. Only the slots whose
+    /// human can no longer cover them (or who is no longer configured) are reassigned, along with any
+    /// newly required slots, with replacements chosen using the same factors as [`Scheduler::schedule`].
+    ///
+    /// The `start` should be the same as the one used to generate the published schedule, so that newly
+    /// required slots are grouped into the same shifts as they would have been originally.
+    pub fn reschedule(&mut self, start: DateTime<Utc>, published: &[ScheduleSlot]) -> Vec<ScheduleSlot> {
+        let mut published = published.to_vec();
+        published.sort_by_key(|slot| slot.time);
+
+        let start = start.naive_utc();
+        let end = match published.iter().map(|slot| slot.time.end).max() {
+            Some(end) => end,
+            None => return vec![],
+        };
+
+        // Split the slots which are currently required at the boundaries of the published slots, so that
+        // each part keeps the human who was assigned to it (and newly required parts have nobody).
+        let mut required = Vec::new();
+        for range in self.config.slots(start, end) {
+            let mut cursor = range.start;
+            for slot in published.iter().filter(|slot| slot.time.end > range.start && slot.time.start < range.end) {
+                if slot.time.start > cursor {
+                    required.push(ScheduleSlot { time: TimeRange::new(cursor, slot.time.start), human: None });
+                }
+
+                let part = TimeRange::new(cursor.max(slot.time.start), range.end.min(slot.time.end));
+                if part.end > part.start {
+                    required.push(ScheduleSlot { time: part, human: slot.human.clone() });
+                    cursor = part.end;
+                }
+            }
+
+            if cursor < range.end {
+                required.push(ScheduleSlot { time: TimeRange::new(cursor, range.end), human: None });
+            }
+        }
+
+        let valid: Vec<bool> = required.iter().map(|slot| {
+            slot.human.as_ref()
+                .and_then(|human| self.config.humans.get(human))
+                .map(|info| info.possible_coverage(&[slot.time])[0])
+                .unwrap_or(false)
+        }).collect();
+
+        let origin = self.config.handoff.as_ref().map(|handoff| handoff.origin()).unwrap_or(start);
+
+        let mut slots = Vec::new();
+        let mut index = 0;
+        while index < required.len() {
+            if valid[index] {
+                let kept = vec![required[index].clone()];
+                self.update_factors(&kept);
+                slots.extend(kept);
+                index += 1;
+                continue;
+            }
+
+            // Consecutive slots which belonged to the same human are reassigned together, so that
+            // their replacement covers as much of the original shift as possible, while newly
+            // required slots are grouped into shifts in the same way as a new schedule.
+            let start = index;
+            while index < required.len() && !valid[index] && required[index].human == required[start].human {
+                index += 1;
+            }
+
+            let slots_to_fill: Vec<TimeRange> = required[start..index].iter().map(|slot| slot.time).collect();
+            let shifts = match required[start].human {
+                Some(_) => vec![slots_to_fill],
+                None => Self::shifts(origin, self.config.shift_length, slots_to_fill),
+            };
+
+            for shift in shifts {
+                let assignments = self.schedule_rotation(&shift);
+                self.update_factors(&assignments);
+                slots.extend(assignments);
+            }
+        }
+
+        slots
+    }
+
+    fn update_factors(&mut self, assignments: &[ScheduleSlot]) {
+        for assignment in assignments {
+            for factor in self.factors.iter_mut() {
+                factor.update(assignment);
+            }
+        }
+    }
+
+    /// Groups the slots which need to be filled into shifts, with a new shift starting every
+    /// `shift_length` after the `origin`. Slots which straddle a shift boundary are split at
+    /// that boundary so that each shift covers the same amount of elapsed time.
+    fn shifts(origin: NaiveDateTime, shift_length: Duration, slots: Vec<TimeRange>) -> Vec<Vec<TimeRange>> {
+        let length = shift_length.num_seconds();
+
+        let mut shifts: Vec<(i64, Vec<TimeRange>)> = Vec::new();
+        for mut slot in slots {
+            while !slot.is_zero() {
+                let index = (slot.start - origin).num_seconds().div_euclid(length);
+                let boundary = origin + Duration::seconds((index + 1) * length);
+                let part = TimeRange::new(slot.start, slot.end.min(boundary));
+
+                match shifts.last_mut() {
+                    Some((current, shift)) if *current == index => shift.push(part),
+                    _ => shifts.push((index, vec![part])),
+                }
+
+                slot = TimeRange::new(part.end, slot.end);
+            }
+        }
+
+        shifts.into_iter().map(|(_, shift)| shift).collect()
+    }
+
+    fn schedule_rotation(&mut self, slots_to_fill: &[TimeRange]) -> Vec<ScheduleSlot> {
         if slots_to_fill.is_empty() {
             return vec![];
         }
 
-        let mut candidates = self
-            .config
+        let config = self.config;
+        let mut candidates = config
             .humans
             .iter()
             .map(|(human, info)| {
-                let available_slots = self.possible_coverage(info, slots_to_fill);
+                let available_slots = info.possible_coverage(slots_to_fill);
 
                 let mut candidate = Candidate::new(human, available_slots);
                 for factor in self.factors.iter() {
-                    factor.populate(self.config, slots_to_fill, &mut candidate);
+                    factor.populate(config, slots_to_fill, &mut candidate);
                 }
 
                 candidate
@@ -85,8 +271,15 @@ impl<'a> Scheduler<'a> {
             .filter(|candidate| candidate.is_available())
             .collect::<Vec<_>>();
 
-        candidates.sort_by_key(|candidate| candidate.human);
-        candidates.sort_by_key(|candidate| (i64::max_value() as f64 * candidate.cost()) as i64);
+        match self.seed {
+            Some(seed) => {
+                let rotation = slots_to_fill[0].start.and_utc().timestamp();
+                candidates.sort_by_key(|candidate| tie_break(seed, rotation, candidate.human));
+            },
+            None => candidates.sort_by_key(|candidate| candidate.human),
+        }
+
+        candidates.sort_by_key(|candidate| (i64::MAX as f64 * candidate.cost()) as i64);
 
         if self.debug {
             eprintln!();
@@ -96,6 +289,14 @@ impl<'a> Scheduler<'a> {
             }
         }
 
+        let rankings: Option<Vec<Ranking>> = self.explanations.as_ref().map(|_| candidates.iter().map(|candidate| {
+            Ranking {
+                human: candidate.human.to_string(),
+                cost: candidate.cost(),
+                factors: candidate.factors.iter().map(|(name, cost)| (name.to_string(), *cost)).collect(),
+            }
+        }).collect());
+
         // We then assign slots to the least busy person who can cover them on a first-come, first-serve basis until all slots are filled and/or all candidates have been exhausted
         let mut slot_assignments: Vec<Option<String>> =
             slots_to_fill.iter().map(|_| None).collect();
@@ -114,66 +315,70 @@ impl<'a> Scheduler<'a> {
             }
         }
 
-        slot_assignments
+        let assignments: Vec<ScheduleSlot> = slot_assignments
             .iter()
             .zip(slots_to_fill.iter())
             .map(|(assignment, slot)| ScheduleSlot {
                 time: *slot,
                 human: assignment.clone(),
             })
-            .collect()
-    }
-
-    /// Returns a vector of booleans indicating whether each slot can be covered by the given constraints.
-    fn possible_coverage(&self, human: &Human, slots: &[TimeRange]) -> Vec<bool> {
-        let initial_slots: Box<dyn Iterator<Item = TimeRange>> = Box::new(slots.iter().copied());
+            .collect();
+
+        if let (Some(explanations), Some(candidates)) = (self.explanations.as_mut(), rankings) {
+            explanations.push(Explanation {
+                slots: slots_to_fill.to_vec(),
+                candidates,
+                assignments: assignments.clone(),
+            });
+        }
 
-        let available_slots = human.constraints.iter().fold(initial_slots, |ranges, constraint| {
-            constraint.flat_map(ranges)
-        }).collect::<Vec<_>>();
+        assignments
+    }
+}
 
-        slots
-            .iter()
-            .map(|slot| available_slots.binary_search(slot).is_ok())
-            .collect()
+/// Derives a reproducible pseudo-random value for a human in a given rotation, used to order candidates
+/// whose costs are equal. This is hand-rolled (using FNV-1a and the SplitMix64 finalizer) rather than
+/// relying on [`std::hash::DefaultHasher`], whose output may change between Rust releases.
+fn tie_break(seed: u64, rotation: i64, human: &str) -> u64 {
+    fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
     }
+
+    let hash = human.bytes().fold(0xcbf29ce484222325, |hash: u64, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    mix(mix(seed.wrapping_add((rotation as u64).wrapping_mul(0x9e3779b97f4a7c15))) ^ hash)
 }
 
 #[cfg(test)]
 mod tests {
-    use chrono::{Duration, NaiveDate, NaiveTime};
+    use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
 
-    use crate::{summary, config::Human, constraints::Constraint};
+    use crate::{summary, config::{Handoff, Human}, constraints::Constraint};
 
     use super::*;
 
     #[test]
     fn test_schedule() {
-        let config = Config {
-            shift_length: Duration::days(1),
-            constraints: vec![
-                Constraint::DayOfWeek(vec![
-                    chrono::Weekday::Mon,
-                    chrono::Weekday::Tue,
-                    chrono::Weekday::Wed,
-                    chrono::Weekday::Thu,
-                    chrono::Weekday::Fri,
-                ]),
-                Constraint::TimeOfDay {
-                    start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
-                    end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
-                },
-            ],
-            humans: map![
-                "alice@example.com" => Human::default().with_constraints(vec![
-                    Constraint::DayOfWeek(vec![chrono::Weekday::Mon, chrono::Weekday::Wed, chrono::Weekday::Fri]),
-                ]),
-                "bob@example.com" => Human::default().with_constraints(vec![
-                    Constraint::Unavailable { start: NaiveDate::from_ymd_opt(2022, 12, 23).unwrap(), end: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap() }
-                ]),
-                "claire@example.com" => Human::default()
-            ],
-        };
+        let config = Config::new(Duration::days(1))
+            .with_constraint(Constraint::DayOfWeek(vec![
+                chrono::Weekday::Mon,
+                chrono::Weekday::Tue,
+                chrono::Weekday::Wed,
+                chrono::Weekday::Thu,
+                chrono::Weekday::Fri,
+            ]))
+            .with_constraint(Constraint::TimeOfDay {
+                start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            })
+            .with_human("alice@example.com", Human::default().with_constraints(vec![
+                Constraint::DayOfWeek(vec![chrono::Weekday::Mon, chrono::Weekday::Wed, chrono::Weekday::Fri]),
+            ]))
+            .with_human("bob@example.com", Human::default().with_constraints(vec![
+                Constraint::Unavailable { start: NaiveDate::from_ymd_opt(2022, 12, 23).unwrap(), end: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap() }
+            ]))
+            .with_human("claire@example.com", Human::default());
 
         let schedule = Scheduler::new(&config).schedule(
             NaiveDate::from_ymd_opt(2023, 1, 1)
@@ -206,4 +411,288 @@ mod tests {
             max, 8, "the longest shift should be 8 hours",
         );
     }
+
+    #[test]
+    fn test_sub_day_shifts() {
+        let config = Config::new(Duration::hours(12))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default());
+
+        let schedule = Scheduler::new(&config).schedule(
+            NaiveDate::from_ymd_opt(2023, 1, 1)
+                .unwrap()
+                .and_time(NaiveTime::default())
+                .and_local_timezone(Utc)
+                .unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 8)
+                .unwrap()
+                .and_time(NaiveTime::default())
+                .and_local_timezone(Utc)
+                .unwrap(),
+        );
+
+        assert_eq!(schedule.len(), 14, "a week should be split into 14 shifts of 12 hours each");
+        assert!(
+            schedule.iter().all(|slot| slot.time.len() == Duration::hours(12)),
+            "each shift should last 12 hours"
+        );
+        assert!(
+            schedule.windows(2).all(|pair| pair[0].human != pair[1].human),
+            "shifts should alternate between the two humans"
+        );
+    }
+
+    #[test]
+    fn test_shifts_split_on_elapsed_time() {
+        let origin = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_time(NaiveTime::default());
+        let slots = vec![
+            TimeRange::new(origin + Duration::hours(8), origin + Duration::hours(16)),
+            TimeRange::new(origin + Duration::hours(20), origin + Duration::hours(36)),
+            TimeRange::new(origin + Duration::days(3), origin + Duration::days(3) + Duration::hours(8)),
+        ];
+
+        let shifts = Scheduler::shifts(origin, Duration::days(1), slots);
+
+        assert_eq!(shifts, vec![
+            vec![
+                TimeRange::new(origin + Duration::hours(8), origin + Duration::hours(16)),
+                TimeRange::new(origin + Duration::hours(20), origin + Duration::hours(24)),
+            ],
+            vec![TimeRange::new(origin + Duration::hours(24), origin + Duration::hours(36))],
+            vec![TimeRange::new(origin + Duration::days(3), origin + Duration::days(3) + Duration::hours(8))],
+        ]);
+    }
+
+    #[test]
+    fn test_handoff_is_stable() {
+        let config = Config::new(Duration::days(7))
+            .with_handoff(Handoff {
+                anchor: Some(NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()),
+                weekday: Some(chrono::Weekday::Mon),
+                time: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                ..Default::default()
+            })
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default());
+
+        let schedule = Scheduler::new(&config).schedule(
+            NaiveDate::from_ymd_opt(2023, 1, 5)
+                .unwrap()
+                .and_time(NaiveTime::default())
+                .and_local_timezone(Utc)
+                .unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 31)
+                .unwrap()
+                .and_time(NaiveTime::default())
+                .and_local_timezone(Utc)
+                .unwrap(),
+        );
+
+        assert_eq!(
+            schedule[0].time.end,
+            NaiveDate::from_ymd_opt(2023, 1, 9).unwrap().and_hms_opt(10, 0, 0).unwrap(),
+            "the first shift should end at the next handoff"
+        );
+        assert!(
+            schedule.iter().skip(1).all(|slot| slot.time.start.weekday() == chrono::Weekday::Mon && slot.time.start.time() == NaiveTime::from_hms_opt(10, 0, 0).unwrap()),
+            "every subsequent shift should start on Monday at 10:00"
+        );
+    }
+
+    #[test]
+    fn test_custom_optimizer() {
+        struct Preference;
+
+        impl Optimizer for Preference {
+            fn init(_config: &Config) -> Box<dyn Optimizer> {
+                Box::new(Self)
+            }
+
+            fn name(&self) -> &'static str {
+                "preference"
+            }
+
+            fn update(&mut self, _slot: &ScheduleSlot) {}
+
+            fn cost(&self, _config: &Config, _slots_to_fill: &[TimeRange], candidate: &Candidate) -> Option<f64> {
+                Some(if candidate.human == "bob@example.com" { 0.0 } else { 1.0 })
+            }
+        }
+
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default());
+
+        let mut scheduler = Scheduler::new(&config)
+            .without_optimizer("length")
+            .without_optimizer("recency")
+            .without_optimizer("workload")
+            .with_optimizer(Preference::init(&config));
+
+        let schedule = scheduler.schedule(
+            NaiveDate::from_ymd_opt(2023, 1, 1)
+                .unwrap()
+                .and_time(NaiveTime::default())
+                .and_local_timezone(Utc)
+                .unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 8)
+                .unwrap()
+                .and_time(NaiveTime::default())
+                .and_local_timezone(Utc)
+                .unwrap(),
+        );
+
+        assert!(
+            schedule.iter().all(|slot| slot.human.as_deref() == Some("bob@example.com")),
+            "the custom optimizer should place bob on-call for every shift"
+        );
+    }
+
+    #[test]
+    fn test_reschedule() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default().with_constraints(vec![
+                Constraint::Unavailable { start: NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(), end: NaiveDate::from_ymd_opt(2023, 1, 5).unwrap() },
+            ]))
+            .with_human("bob@example.com", Human::default())
+            .with_human("claire@example.com", Human::default());
+
+        let slot = |day: u32, human: &str| ScheduleSlot {
+            time: TimeRange::new(
+                NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_time(NaiveTime::default()),
+                NaiveDate::from_ymd_opt(2023, 1, day + 1).unwrap().and_time(NaiveTime::default()),
+            ),
+            human: Some(human.to_string()),
+        };
+
+        let published = vec![
+            slot(2, "alice@example.com"),
+            slot(3, "alice@example.com"),
+            slot(4, "alice@example.com"),
+            slot(5, "bob@example.com"),
+            slot(6, "claire@example.com"),
+            slot(7, "dave@example.com"),
+        ];
+
+        let start = published[0].time.start.and_utc();
+        let schedule = Scheduler::new(&config).reschedule(start, &published);
+
+        assert_eq!(schedule.len(), published.len(), "every slot should be preserved");
+        assert_eq!(schedule[0], published[0], "alice's first slot is still valid and should be kept");
+        assert_eq!(&schedule[3..5], &published[3..5], "valid assignments should be kept");
+        assert!(
+            schedule[1..3].iter().all(|slot| slot.human.as_deref() == Some("bob@example.com")),
+            "alice's slots during their leave should be reassigned together to the least busy human"
+        );
+        assert!(
+            schedule[5].human.is_some() && schedule[5].human.as_deref() != Some("dave@example.com"),
+            "slots assigned to humans who are no longer configured should be reassigned"
+        );
+    }
+
+    #[test]
+    fn test_reschedule_required_slots() {
+        let config = Config::new(Duration::days(1))
+            .with_constraint(Constraint::DayOfWeek(vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default());
+
+        let slot = |day: u32, human: &str| ScheduleSlot {
+            time: TimeRange::new(
+                NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_time(NaiveTime::default()),
+                NaiveDate::from_ymd_opt(2023, 1, day + 1).unwrap().and_time(NaiveTime::default()),
+            ),
+            human: Some(human.to_string()),
+        };
+
+        // The published schedule has no slot on Wednesday 2023-01-04, and covers the weekend which is no longer required
+        let published = vec![
+            slot(2, "alice@example.com"),
+            slot(3, "bob@example.com"),
+            slot(5, "alice@example.com"),
+            slot(6, "bob@example.com"),
+            slot(7, "alice@example.com"),
+            slot(8, "bob@example.com"),
+        ];
+
+        let start = published[0].time.start.and_utc();
+        let schedule = Scheduler::new(&config).reschedule(start, &published);
+
+        assert_eq!(schedule.len(), 5, "the weekend slots should be dropped and Wednesday's slot added");
+        assert_eq!(&schedule[0..2], &published[0..2], "valid assignments should be kept");
+        assert_eq!(schedule[2].time, slot(4, "alice@example.com").time);
+        assert!(schedule[2].human.is_some(), "newly required slots should be assigned");
+        assert_eq!(&schedule[3..5], &published[2..4], "valid assignments should be kept");
+    }
+
+    #[test]
+    fn test_reschedule_shift_origin() {
+        let config = Config::new(Duration::days(2))
+            .with_constraint(Constraint::DayOfWeek(vec![Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun]))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default());
+
+        let day = |day: u32| NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_time(NaiveTime::default());
+        let slot = |from: u32, to: u32, human: Option<&str>| ScheduleSlot {
+            time: TimeRange::new(day(from), day(to)),
+            human: human.map(|h| h.to_string()),
+        };
+
+        // The schedule was generated from Monday 2023-01-02, which is no longer required, so its first slot starts on Tuesday
+        let published = vec![
+            slot(3, 4, Some("alice@example.com")),
+            slot(8, 9, Some("bob@example.com")),
+        ];
+
+        let schedule = Scheduler::new(&config).reschedule(day(2).and_utc(), &published);
+
+        assert_eq!(
+            schedule.iter().map(|slot| slot.time).collect::<Vec<_>>(),
+            vec![slot(3, 4, None).time, slot(4, 6, None).time, slot(6, 8, None).time, slot(8, 9, None).time],
+            "newly required slots should be grouped into shifts counted from the original start"
+        );
+    }
+
+    #[test]
+    fn test_explanations() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default().with_prior_workload(Duration::hours(24)));
+
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_time(NaiveTime::default()).and_utc();
+        let mut scheduler = Scheduler::new(&config).with_explanations();
+        let schedule = scheduler.schedule(start, start + Duration::days(2));
+
+        let explanations = scheduler.explanations();
+        assert_eq!(explanations.len(), 2, "each rotation should be explained");
+        assert_eq!(explanations[0].assignments, schedule[0..1]);
+        assert_eq!(explanations[0].candidates[0].human, "alice@example.com", "alice has the lowest workload and should be ranked first");
+        assert!(explanations[0].candidates[1].factors.contains_key("workload"));
+    }
+
+    #[test]
+    fn test_seeded_tie_breaking() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default())
+            .with_human("claire@example.com", Human::default());
+
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let end = start + Duration::days(14);
+        let schedule = |seed: Option<u64>| {
+            let mut scheduler = Scheduler::new(&config);
+            if let Some(seed) = seed {
+                scheduler = scheduler.with_seed(seed);
+            }
+
+            scheduler.schedule(start, end)
+        };
+
+        assert_eq!(schedule(None)[0].human.as_deref(), Some("alice@example.com"), "ties are broken by name without a seed");
+        assert_eq!(schedule(Some(42)), schedule(Some(42)), "the same seed should produce the same schedule");
+
+        let schedules: Vec<_> = (0..10).map(|seed| schedule(Some(seed))).collect();
+        assert!(schedules.iter().any(|s| *s != schedules[0]), "different seeds should produce different schedules");
+        assert!(schedules.iter().all(|s| s.iter().all(|slot| slot.human.is_some())), "all slots must be filled");
+    }
 }
@@ -1,61 +1,88 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::{BTreeMap, HashMap}, fmt::Display};
 
-use chrono::Duration;
+use chrono::{Datelike, Duration, Timelike};
+use serde::Serialize;
 
 use crate::{solver::ScheduleSlot, config::Config};
 
+/// The hours of the day (in UTC, like every time in the schedule) which are considered to be at night, spanning midnight.
+const NIGHT_START: u32 = 22;
+const NIGHT_END: u32 = 6;
+
 pub struct Summary {
     workload: HashMap<String, Duration>,
     longest_shift: HashMap<String, Duration>,
     shift_length_histogram: HashMap<i64, usize>,
+    capacity: HashMap<String, f64>,
+    shift_count: HashMap<String, usize>,
+    block_count: HashMap<String, usize>,
+    weekday_hours: HashMap<String, [Duration; 7]>,
+    hourly_hours: HashMap<String, [Duration; 24]>,
+}
+
+/// Measures of how evenly the workload has been distributed between humans, after accounting for their capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Fairness {
+    /// The standard deviation of each human's workload, in hours.
+    pub stddev: f64,
+    /// The Gini coefficient of the workload, where 0 is perfectly fair and 1 means a single human did everything.
+    pub gini: f64,
+    /// The ratio between the largest and smallest workloads, or `None` if somebody was never on-call.
+    #[serde(rename = "maxMinRatio")]
+    pub max_min_ratio: Option<f64>,
 }
 
 impl<T: AsRef<[ScheduleSlot]>> From<T> for Summary {
     fn from(schedule: T) -> Self {
-        let mut workload = map! {};
-        let mut longest_shift = map! {};
-        let mut shift_length_histogram = map!{};
+        let mut workload: HashMap<String, Duration> = map! {};
+        let mut longest_shift: HashMap<String, Duration> = map! {};
+        let mut shift_length_histogram: HashMap<i64, usize> = map!{};
+        let mut shift_count: HashMap<String, usize> = map!{};
+        let mut block_count: HashMap<String, usize> = map!{};
+        let mut weekday_hours: HashMap<String, [Duration; 7]> = map!{};
+        let mut hourly_hours: HashMap<String, [Duration; 24]> = map!{};
 
-        let mut current_on_call: Option<(String, Duration)> = None;
+        // Schedules which have been edited by hand may not be in chronological order
+        let mut schedule: Vec<&ScheduleSlot> = schedule.as_ref().iter().collect();
+        schedule.sort_by_key(|slot| slot.time);
 
-        for slot in schedule.as_ref() {
+        let mut blocks: Vec<(&str, Duration)> = Vec::new();
+        for slot in schedule {
             let human = slot.human.as_deref().unwrap_or("UNASSIGNED");
 
-            workload
-                .entry(human.to_string())
-                .and_modify(|e| *e = *e + slot.time.len())
-                .or_insert_with(|| slot.time.len());
-
-            let shift_len = if let Some((person, length)) = current_on_call {
-                let new_length = if person == human {
-                    length + slot.time.len()
-                } else {
-                    shift_length_histogram.entry(length.num_hours()).and_modify(|e| *e += 1).or_insert(1);
-                    slot.time.len()
-                };
+            *workload.entry(human.to_string()).or_insert_with(Duration::zero) += slot.time.len();
+            Self::distribute(
+                &slot.time,
+                weekday_hours.entry(human.to_string()).or_insert([Duration::zero(); 7]),
+                hourly_hours.entry(human.to_string()).or_insert([Duration::zero(); 24]),
+            );
+            *shift_count.entry(human.to_string()).or_insert(0) += 1;
 
-                current_on_call = Some((human.to_string(), new_length));
+            match blocks.last_mut() {
+                Some((person, length)) if *person == human => *length += slot.time.len(),
+                _ => blocks.push((human, slot.time.len())),
+            }
+        }
 
-                new_length
-            } else {
-                current_on_call = Some((human.to_string(), slot.time.len()));
-                slot.time.len()
-            };
+        for (human, length) in blocks {
+            *shift_length_histogram.entry(length.num_hours()).or_insert(0) += 1;
+            *block_count.entry(human.to_string()).or_insert(0) += 1;
 
-            longest_shift
-                .entry(human.to_string())
-                .and_modify(|e| {
-                    if *e < shift_len {
-                        *e = shift_len;
-                    }
-                })
-                .or_insert(shift_len);
+            let longest = longest_shift.entry(human.to_string()).or_insert(length);
+            if *longest < length {
+                *longest = length;
+            }
         }
 
         Self {
             workload,
             longest_shift,
-            shift_length_histogram
+            shift_length_histogram,
+            capacity: map!{},
+            shift_count,
+            block_count,
+            weekday_hours,
+            hourly_hours,
         }
     }
 }
@@ -68,28 +95,153 @@ impl Summary {
 
         let min = items.first().copied().unwrap_or_else(Duration::zero);
         let max = items.last().copied().unwrap_or_else(Duration::zero);
-        let avg = items.iter().sum::<Duration>() / items.len() as i32;
+        let avg = match items.len() {
+            0 => Duration::zero(),
+            len => items.iter().sum::<Duration>() / len as i32,
+        };
 
         (min.num_hours(), avg.num_hours(), max.num_hours())
     }
 
+    /// Picks a character representing how close a value is to the peak value in a heatmap.
+    fn shade(value: Duration, peak: Duration) -> char {
+        const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+        if value <= Duration::zero() || peak <= Duration::zero() {
+            return SHADES[0];
+        }
+
+        let level = (value.num_seconds() as f64 / peak.num_seconds() as f64 * 4.0).ceil() as usize;
+        SHADES[level.clamp(1, 4)]
+    }
+
+    /// Adds the time within a range to the day of the week (starting on Monday) and hour of the day on which it falls.
+    fn distribute(range: &crate::timerange::TimeRange, weekdays: &mut [Duration; 7], hours: &mut [Duration; 24]) {
+        let mut cursor = range.start;
+        while cursor < range.end {
+            let next = (cursor.date().and_hms_opt(cursor.hour(), 0, 0).unwrap() + Duration::hours(1)).min(range.end);
+
+            weekdays[cursor.weekday().num_days_from_monday() as usize] += next - cursor;
+            hours[cursor.hour() as usize] += next - cursor;
+
+            cursor = next;
+        }
+    }
+
     pub fn with_adjustments(self, config: &Config) -> Self {
         let mut workload = self.workload;
+        let mut capacity = self.capacity;
 
         for (name, info) in config.humans.iter() {
             if let Some(duration) = workload.get_mut(name) {
-                *duration = *duration + info.prior_workload;
+                *duration += info.prior_workload;
             } else {
                 workload.insert(name.clone(), info.prior_workload);
             }
+
+            capacity.insert(name.clone(), info.capacity);
         }
 
         Self {
             workload,
+            capacity,
             ..self
         }
     }
 
+    /// Calculates the number of hours each human is ahead of their fair share of the workload,
+    /// taking their capacity into account. These values can be fed back into the `priorWorkload`
+    /// of each human when generating the next schedule.
+    pub fn future_adjustments(&self) -> HashMap<String, i64> {
+        let capacity = |human: &str| self.capacity.get(human).copied().unwrap_or(1.0);
+
+        let min = self.workload.iter()
+            .filter(|(human, _)| capacity(human) > 0.0)
+            .map(|(human, workload)| workload.num_seconds() as f64 / capacity(human))
+            .fold(f64::INFINITY, f64::min);
+        let min = if min.is_finite() { min } else { 0.0 };
+
+        self.workload.iter().map(|(human, workload)| {
+            let expected = Duration::seconds((min * capacity(human)) as i64);
+            (human.clone(), (*workload - expected).num_hours())
+        }).collect()
+    }
+
+    /// The total amount of time the given human has been on-call for.
+    pub fn workload(&self, human: &str) -> Duration {
+        self.workload.get(human).copied().unwrap_or_else(Duration::zero)
+    }
+
+    /// The longest continuous period the given human has been on-call for.
+    pub fn longest_shift(&self, human: &str) -> Duration {
+        self.longest_shift.get(human).copied().unwrap_or_else(Duration::zero)
+    }
+
+    /// The number of slots the given human has been assigned.
+    pub fn shift_count(&self, human: &str) -> usize {
+        self.shift_count.get(human).copied().unwrap_or_default()
+    }
+
+    /// The number of distinct (non-consecutive) blocks of slots the given human has been on-call for.
+    pub fn block_count(&self, human: &str) -> usize {
+        self.block_count.get(human).copied().unwrap_or_default()
+    }
+
+    /// The amount of time the given human has been on-call for on each day of the week, starting on Monday.
+    pub fn weekday_hours(&self, human: &str) -> [Duration; 7] {
+        self.weekday_hours.get(human).copied().unwrap_or([Duration::zero(); 7])
+    }
+
+    /// The amount of time the given human has been on-call for during each hour of the day.
+    pub fn hourly_hours(&self, human: &str) -> [Duration; 24] {
+        self.hourly_hours.get(human).copied().unwrap_or([Duration::zero(); 24])
+    }
+
+    /// The amount of time the given human has been on-call for on Saturdays and Sundays.
+    pub fn weekend_hours(&self, human: &str) -> Duration {
+        self.weekday_hours(human)[5..].iter().sum()
+    }
+
+    /// The amount of time the given human has been on-call for at night (between 22:00 and 06:00).
+    pub fn night_hours(&self, human: &str) -> Duration {
+        self.hourly_hours(human).iter().enumerate()
+            .filter(|(hour, _)| *hour as u32 >= NIGHT_START || (*hour as u32) < NIGHT_END)
+            .map(|(_, d)| *d)
+            .sum()
+    }
+
+    /// Measures how evenly the workload has been distributed between the humans in the schedule, with
+    /// each human's workload divided by their capacity. Unassigned slots are not included.
+    pub fn fairness(&self) -> Fairness {
+        let capacity = |human: &str| self.capacity.get(human).copied().unwrap_or(1.0);
+
+        let mut hours: Vec<f64> = self.workload.iter()
+            .filter(|(human, _)| human.as_str() != "UNASSIGNED" && capacity(human) > 0.0)
+            .map(|(human, workload)| workload.num_seconds() as f64 / 3600.0 / capacity(human))
+            .collect();
+        hours.sort_by(f64::total_cmp);
+
+        let n = hours.len() as f64;
+        let total: f64 = hours.iter().sum();
+        if hours.is_empty() || total <= 0.0 {
+            return Fairness { stddev: 0.0, gini: 0.0, max_min_ratio: None };
+        }
+
+        let mean = total / n;
+        let stddev = (hours.iter().map(|h| (h - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let gini = hours.iter().enumerate()
+            .map(|(i, h)| (2.0 * (i as f64 + 1.0) - n - 1.0) * h)
+            .sum::<f64>() / (n * total);
+
+        let (min, max) = (hours[0], hours[hours.len() - 1]);
+
+        Fairness {
+            stddev,
+            gini,
+            max_min_ratio: if min > 0.0 { Some(max / min) } else { None },
+        }
+    }
+
     pub fn workload_stats(&self) -> (i64, i64, i64) {
         Self::stats(self.workload.values().copied())
     }
@@ -99,19 +251,88 @@ impl Summary {
     }
 }
 
+impl Serialize for Summary {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        #[derive(Serialize)]
+        struct Stats {
+            min: i64,
+            avg: i64,
+            max: i64,
+        }
+
+        impl From<(i64, i64, i64)> for Stats {
+            fn from((min, avg, max): (i64, i64, i64)) -> Self {
+                Self { min, avg, max }
+            }
+        }
+
+        let hours = |durations: &HashMap<String, Duration>| -> BTreeMap<String, f64> {
+            durations.iter().map(|(human, d)| (human.clone(), d.num_seconds() as f64 / 3600.0)).collect()
+        };
+
+        let mut state = serializer.serialize_struct("Summary", 13)?;
+        state.serialize_field("workload", &hours(&self.workload))?;
+        state.serialize_field("workloadStats", &Stats::from(self.workload_stats()))?;
+        state.serialize_field("fairness", &self.fairness())?;
+        state.serialize_field("longestShift", &hours(&self.longest_shift))?;
+        state.serialize_field("longestShiftStats", &Stats::from(self.longest_shift_stats()))?;
+        state.serialize_field("shiftLengthHistogram", &self.shift_length_histogram.iter().collect::<BTreeMap<_, _>>())?;
+        state.serialize_field("futureAdjustments", &self.future_adjustments().into_iter().collect::<BTreeMap<_, _>>())?;
+        state.serialize_field("shifts", &self.shift_count.iter().collect::<BTreeMap<_, _>>())?;
+        state.serialize_field("blocks", &self.block_count.iter().collect::<BTreeMap<_, _>>())?;
+        let as_hours = |d: &Duration| d.num_seconds() as f64 / 3600.0;
+        let per_human = |f: &dyn Fn(&str) -> Duration| -> BTreeMap<&String, f64> {
+            self.shift_count.keys().map(|human| (human, as_hours(&f(human)))).collect()
+        };
+
+        state.serialize_field("weekendHours", &per_human(&|human| self.weekend_hours(human)))?;
+        state.serialize_field("nightHours", &per_human(&|human| self.night_hours(human)))?;
+        state.serialize_field("weekdayHours", &self.weekday_hours.iter()
+            .map(|(human, row)| (human, row.iter().map(as_hours).collect::<Vec<_>>()))
+            .collect::<BTreeMap<_, _>>())?;
+        state.serialize_field("hourlyHours", &self.hourly_hours.iter()
+            .map(|(human, row)| (human, row.iter().map(as_hours).collect::<Vec<_>>()))
+            .collect::<BTreeMap<_, _>>())?;
+        state.end()
+    }
+}
+
 impl Display for Summary {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut workload: Vec<_> = self.workload.iter().collect();
-        workload.sort_by_key(|(_, v)| -v.num_hours());
+        workload.sort_by_key(|(human, v)| (-v.num_hours(), *human));
         let (wl_min, wl_avg, wl_max) = Self::stats(self.workload.values().copied());
 
         let mut longest_shift: Vec<_> = self.longest_shift.iter().collect();
-        longest_shift.sort_by_key(|(_, v)| -v.num_hours());
+        longest_shift.sort_by_key(|(human, v)| (-v.num_hours(), *human));
         let (ls_min, ls_avg, ls_max) = Self::stats(self.longest_shift.values().copied());
 
+        let adjustments = self.future_adjustments();
+        let fairness = self.fairness();
+
         writeln!(f, "Workload: (min: {wl_min}, avg: {wl_avg}, max: {wl_max})")?;
-        for (human, workload) in workload {
-            writeln!(f, "  {}: {} hours (future adjustment: {})", human, workload.num_hours(), workload.num_hours() - wl_min)?;
+        for (human, workload) in workload.iter() {
+            writeln!(f, "  {}: {} hours (future adjustment: {})", human, workload.num_hours(), adjustments.get(human.as_str()).copied().unwrap_or_default())?;
+        }
+
+        writeln!(f)?;
+        write!(f, "Fairness: (stddev: {:.1} hours, gini: {:.3}", fairness.stddev, fairness.gini)?;
+        match fairness.max_min_ratio {
+            Some(ratio) => writeln!(f, ", max/min: {:.2})", ratio)?,
+            None => writeln!(f, ", max/min: n/a)")?,
+        }
+        for (human, _) in workload.iter().filter(|(human, _)| self.shift_count(human) > 0) {
+            writeln!(
+                f,
+                "  {}: {} shift(s) in {} block(s), {} weekend hours, {} night hours",
+                human,
+                self.shift_count(human),
+                self.block_count(human),
+                self.weekend_hours(human).num_hours(),
+                self.night_hours(human).num_hours(),
+            )?;
         }
 
         writeln!(f)?;
@@ -122,10 +343,167 @@ impl Display for Summary {
 
         writeln!(f)?;
         writeln!(f, "Shift length histogram:")?;
-        for (length, count) in self.shift_length_histogram.iter() {
+        for (length, count) in self.shift_length_histogram.iter().collect::<BTreeMap<_, _>>() {
             writeln!(f, " {} | {} hours", count, length)?;
         }
 
+        let mut humans: Vec<&String> = self.shift_count.keys().collect();
+        humans.sort();
+        let width = humans.iter().map(|h| h.len()).max().unwrap_or_default();
+
+        let peak = self.weekday_hours.values().flat_map(|row| row.iter()).max().copied().unwrap_or_else(Duration::zero);
+        writeln!(f)?;
+        writeln!(f, "Weekday heatmap (hours):")?;
+        writeln!(f, "  {:width$} {}", "", ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"].map(|d| format!("{:>5}", d)).join(""))?;
+        for human in humans.iter() {
+            let cells: String = self.weekday_hours(human).iter().map(|d| format!(" {}{:>3}", Self::shade(*d, peak), d.num_hours())).collect();
+            writeln!(f, "  {:width$} {}", human, cells)?;
+        }
+
+        let peak = self.hourly_hours.values().flat_map(|row| row.iter()).max().copied().unwrap_or_else(Duration::zero);
+        writeln!(f)?;
+        writeln!(f, "Hour of day heatmap:")?;
+        writeln!(f, "  {:width$} {:<6}{:<6}{:<6}18", "", 0, 6, 12)?;
+        for human in humans.iter() {
+            let cells: String = self.hourly_hours(human).iter().map(|d| Self::shade(*d, peak)).collect();
+            writeln!(f, "  {:width$} {}", human, cells)?;
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{config::Human, timerange::TimeRange};
+
+    use super::*;
+
+    #[test]
+    fn test_future_adjustments_with_capacity() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default().with_capacity(0.5))
+            .with_human("bob@example.com", Human::default());
+
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let schedule = vec![
+            ScheduleSlot { time: TimeRange::new(start, start + Duration::hours(20)), human: Some("alice@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::hours(20), start + Duration::hours(60)), human: Some("bob@example.com".into()) },
+        ];
+
+        let adjustments = Summary::from(&schedule).with_adjustments(&config).future_adjustments();
+        assert_eq!(adjustments["alice@example.com"], 0, "alice carried exactly their share of the workload");
+        assert_eq!(adjustments["bob@example.com"], 0, "bob carried exactly their share of the workload");
+    }
+
+    #[test]
+    fn test_empty_schedule() {
+        let summary = Summary::from(Vec::<ScheduleSlot>::new());
+
+        assert_eq!(summary.workload_stats(), (0, 0, 0));
+        assert_eq!(summary.longest_shift_stats(), (0, 0, 0));
+        assert_eq!(summary.fairness(), Fairness { stddev: 0.0, gini: 0.0, max_min_ratio: None });
+        assert!(!format!("{}", summary).is_empty());
+    }
+
+    #[test]
+    fn test_unordered_schedule() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let slot = |offset: i64, human: &str| ScheduleSlot {
+            time: TimeRange::new(start + Duration::hours(offset), start + Duration::hours(offset + 8)),
+            human: Some(human.into()),
+        };
+
+        let summary = Summary::from(vec![
+            slot(16, "alice@example.com"),
+            slot(8, "bob@example.com"),
+            slot(0, "alice@example.com"),
+        ]);
+
+        assert_eq!(summary.longest_shift("alice@example.com"), Duration::hours(8), "alice's slots are not consecutive once sorted");
+        assert_eq!(summary.workload("alice@example.com"), Duration::hours(16));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let schedule = vec![
+            ScheduleSlot { time: TimeRange::new(start, start + Duration::hours(24)), human: Some("alice@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::hours(24), start + Duration::hours(36)), human: Some("bob@example.com".into()) },
+            ScheduleSlot { time: TimeRange::new(start + Duration::hours(36), start + Duration::hours(48)), human: Some("alice@example.com".into()) },
+        ];
+
+        let json = serde_json::to_value(Summary::from(&schedule)).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "workload": { "alice@example.com": 36.0, "bob@example.com": 12.0 },
+            "workloadStats": { "min": 12, "avg": 24, "max": 36 },
+            "fairness": { "stddev": 12.0, "gini": 0.25, "maxMinRatio": 3.0 },
+            "longestShift": { "alice@example.com": 24.0, "bob@example.com": 12.0 },
+            "longestShiftStats": { "min": 12, "avg": 18, "max": 24 },
+            "shiftLengthHistogram": { "24": 1, "12": 2 },
+            "futureAdjustments": { "alice@example.com": 24, "bob@example.com": 0 },
+            "shifts": { "alice@example.com": 2, "bob@example.com": 1 },
+            "blocks": { "alice@example.com": 2, "bob@example.com": 1 },
+            "weekendHours": { "alice@example.com": 0.0, "bob@example.com": 0.0 },
+            "nightHours": { "alice@example.com": 10.0, "bob@example.com": 6.0 },
+            "weekdayHours": {
+                "alice@example.com": [24.0, 12.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                "bob@example.com": [0.0, 12.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            },
+            "hourlyHours": {
+                "alice@example.com": (0..24).map(|hour| if hour < 12 { 1.0 } else { 2.0 }).collect::<Vec<f64>>(),
+                "bob@example.com": (0..24).map(|hour| if hour < 12 { 1.0 } else { 0.0 }).collect::<Vec<f64>>(),
+            },
+        }));
+    }
+
+    #[test]
+    fn test_heatmap() {
+        // Friday 20:00 until Saturday 08:00
+        let start = NaiveDate::from_ymd_opt(2023, 1, 6).unwrap().and_hms_opt(20, 0, 0).unwrap();
+        let summary = Summary::from(vec![ScheduleSlot {
+            time: TimeRange::new(start, start + Duration::hours(12)),
+            human: Some("alice@example.com".into()),
+        }]);
+
+        let weekdays = summary.weekday_hours("alice@example.com");
+        assert_eq!(weekdays[4], Duration::hours(4), "alice was on-call for 4 hours on Friday");
+        assert_eq!(weekdays[5], Duration::hours(8), "alice was on-call for 8 hours on Saturday");
+
+        let hours = summary.hourly_hours("alice@example.com");
+        assert_eq!(hours[20], Duration::hours(1));
+        assert_eq!(hours[8], Duration::zero());
+        assert_eq!(hours.iter().sum::<Duration>(), Duration::hours(12));
+
+        assert_eq!(summary.weekend_hours("alice@example.com"), Duration::hours(8));
+        assert_eq!(summary.night_hours("alice@example.com"), Duration::hours(8));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["weekdayHours"]["alice@example.com"], serde_json::json!([0.0, 0.0, 0.0, 0.0, 4.0, 8.0, 0.0]));
+    }
+
+    #[test]
+    fn test_fairness() {
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let schedule: Vec<ScheduleSlot> = ["alice@example.com", "bob@example.com", "alice@example.com", "bob@example.com"].iter().enumerate()
+            .map(|(i, human)| ScheduleSlot {
+                time: TimeRange::new(start + Duration::days(i as i64), start + Duration::days(i as i64 + 1)),
+                human: Some(human.to_string()),
+            })
+            .collect();
+
+        let fairness = Summary::from(&schedule).fairness();
+        assert_eq!(fairness, Fairness { stddev: 0.0, gini: 0.0, max_min_ratio: Some(1.0) });
+
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default())
+            .with_human("claire@example.com", Human::default());
+
+        let fairness = Summary::from(&schedule).with_adjustments(&config).fairness();
+        assert_eq!(fairness.max_min_ratio, None, "claire was never on-call");
+        assert!((fairness.gini - 1.0 / 3.0).abs() < 1e-9, "gini was {}", fairness.gini);
+    }
+}
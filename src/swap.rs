@@ -0,0 +1,242 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+
+use crate::{block::Block, config::Config, solver::ScheduleSlot, summary::Summary};
+
+/// A human who could take a slot from someone else, along with a slot of theirs which could be
+/// given back in exchange (if any) and the effect the trade would have on the schedule's fairness.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Swap {
+    pub human: String,
+    pub take: ScheduleSlot,
+    pub give_back: Option<ScheduleSlot>,
+    /// The change in the standard deviation of everyone's workload (adjusted for their capacity), in hours.
+    #[serde(rename = "stddevChange")]
+    pub stddev_change: f64,
+    /// The change in the longest continuous shift worked by anyone, in hours.
+    #[serde(rename = "longestShiftChange")]
+    pub longest_shift_change: i64,
+}
+
+/// Finds the humans who could take the slot at `index` in the `schedule`, ranked by how little the
+/// trade would harm the fairness of the schedule. Every swap respects the constraints of the humans
+/// involved and will not extend anyone's continuous time on-call (the elapsed length of a block of
+/// contiguous slots) beyond the configured shift length (or their existing longest block, if that is
+/// already longer).
+pub fn find_swaps(config: &Config, schedule: &[ScheduleSlot], index: usize) -> Vec<Swap> {
+    let slot = &schedule[index];
+    let owner = match slot.human.as_deref() {
+        Some(owner) => owner,
+        None => return vec![],
+    };
+
+    let before = Summary::from(schedule).with_adjustments(config);
+    let stddev = before.fairness().stddev;
+    let (_, _, longest) = before.longest_shift_stats();
+
+    let can_cover = |human: &str, slot: &ScheduleSlot| {
+        config.humans.get(human).map(|info| info.possible_coverage(&[slot.time])[0]).unwrap_or(false)
+    };
+
+    let before_blocks = Block::merge(schedule);
+    let is_rested = |blocks: &[Block], human: &str| {
+        longest_block(blocks, human) <= config.shift_length.max(longest_block(&before_blocks, human))
+    };
+
+    let evaluate = |human: &str, give_back: Option<usize>| -> Option<Swap> {
+        let mut swapped = schedule.to_vec();
+        swapped[index].human = Some(human.to_string());
+        if let Some(give_back) = give_back {
+            swapped[give_back].human = Some(owner.to_string());
+        }
+
+        let blocks = Block::merge(&swapped);
+        if !is_rested(&blocks, human) || !is_rested(&blocks, owner) {
+            return None;
+        }
+
+        let after = Summary::from(&swapped).with_adjustments(config);
+
+        let (_, _, new_longest) = after.longest_shift_stats();
+
+        Some(Swap {
+            human: human.to_string(),
+            take: slot.clone(),
+            give_back: give_back.map(|i| schedule[i].clone()),
+            stddev_change: after.fairness().stddev - stddev,
+            longest_shift_change: new_longest - longest,
+        })
+    };
+
+    let mut humans: Vec<&String> = config.humans.keys().filter(|human| human.as_str() != owner).collect();
+    humans.sort();
+
+    let mut swaps: Vec<Swap> = humans.into_iter()
+        .filter(|human| can_cover(human, slot))
+        .filter_map(|human| {
+            schedule.iter().enumerate()
+                .filter(|(i, other)| *i != index && other.human.as_deref() == Some(human.as_str()))
+                .filter(|(_, other)| other.time.intersection(&slot.time).map(|i| i.is_zero()).unwrap_or(true))
+                .filter(|(_, other)| can_cover(owner, other))
+                .filter_map(|(i, _)| evaluate(human, Some(i)))
+                .chain(evaluate(human, None))
+                .min_by_key(Swap::rank)
+        })
+        .collect();
+
+    swaps.sort_by_key(Swap::rank);
+    swaps
+}
+
+/// The elapsed length of the longest block of contiguous slots assigned to the human.
+fn longest_block(blocks: &[Block], human: &str) -> Duration {
+    blocks.iter()
+        .filter(|block| block.human.as_deref() == Some(human))
+        .map(|block| block.time.len())
+        .max()
+        .unwrap_or_else(Duration::zero)
+}
+
+/// Finds the index of the slot which the human is on-call for at the given time, or of their first slot
+/// starting later on the same day, so that a date alone is enough to pick out a day's shift.
+pub fn find_slot(schedule: &[ScheduleSlot], human: &str, at: NaiveDateTime) -> Option<usize> {
+    let owned = |slot: &&ScheduleSlot| slot.human.as_deref() == Some(human);
+
+    schedule.iter().position(|slot| owned(&slot) && slot.time.start <= at && slot.time.end > at)
+        .or_else(|| schedule.iter().position(|slot| owned(&slot) && slot.time.start >= at && slot.time.start.date() == at.date()))
+}
+
+impl Swap {
+    /// Swaps are ranked by their impact on workload fairness (adjusted for each human's capacity) first,
+    /// then by their impact on shift length, preferring trades over one-way handoffs where both are
+    /// otherwise equal. Fairness is compared to the nearest minute so that rounding errors don't break ties.
+    fn rank(&self) -> (i64, i64, bool, Duration) {
+        let give_back_distance = self.give_back.as_ref()
+            .map(|give_back| (give_back.time.start - self.take.time.start).abs())
+            .unwrap_or_else(Duration::zero);
+
+        ((self.stddev_change * 60.0).round() as i64, self.longest_shift_change, self.give_back.is_none(), give_back_distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use crate::{config::Human, constraints::Constraint, timerange::TimeRange};
+
+    use super::*;
+
+    fn slot(day: u32, human: &str) -> ScheduleSlot {
+        let start = NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        ScheduleSlot {
+            time: TimeRange::new(start, start + Duration::days(1)),
+            human: Some(human.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_find_swaps() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default())
+            .with_human("claire@example.com", Human::default().with_constraints(vec![
+                Constraint::Unavailable { start: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(), end: NaiveDate::from_ymd_opt(2023, 1, 3).unwrap() },
+            ]))
+            .with_human("dave@example.com", Human::default());
+
+        let schedule = vec![
+            slot(2, "alice@example.com"),
+            slot(3, "bob@example.com"),
+            slot(4, "claire@example.com"),
+            slot(5, "dave@example.com"),
+            slot(6, "alice@example.com"),
+        ];
+
+        let swaps = find_swaps(&config, &schedule, 0);
+
+        assert!(swaps.iter().all(|swap| swap.human != "claire@example.com"), "claire is unavailable and cannot take the slot");
+        assert_eq!(swaps.len(), 2);
+
+        assert_eq!(swaps[0].human, "bob@example.com");
+        assert_eq!(swaps[0].give_back, Some(slot(3, "bob@example.com")), "bob should trade their own slot back");
+        assert_eq!(swaps[0].stddev_change, 0.0);
+
+        assert_eq!(swaps[1].human, "dave@example.com");
+        assert_eq!(swaps[1].give_back, None, "taking dave's slot would leave alice working back-to-back shifts");
+    }
+
+    #[test]
+    fn test_find_swaps_capacity() {
+        let config = Config::new(Duration::days(1))
+            .with_human("alice@example.com", Human::default().with_constraints(vec![
+                Constraint::Unavailable { start: NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(), end: NaiveDate::from_ymd_opt(2023, 1, 7).unwrap() },
+            ]))
+            .with_human("bob@example.com", Human::default().with_capacity(0.5))
+            .with_human("dave@example.com", Human::default())
+            .with_human("erin@example.com", Human::default());
+
+        let schedule = vec![
+            slot(2, "alice@example.com"),
+            slot(3, "erin@example.com"),
+            slot(4, "bob@example.com"),
+            slot(5, "erin@example.com"),
+            slot(6, "dave@example.com"),
+        ];
+
+        let swaps = find_swaps(&config, &schedule, 0);
+
+        assert_eq!(swaps.iter().map(|swap| swap.human.as_str()).collect::<Vec<_>>(), vec!["dave@example.com", "bob@example.com"]);
+        assert!(swaps[1].stddev_change > swaps[0].stddev_change, "bob is part-time, so an extra slot is a larger share of their capacity");
+    }
+
+    #[test]
+    fn test_find_slot() {
+        let schedule = vec![
+            slot(2, "alice@example.com"),
+            slot(3, "bob@example.com"),
+            slot(4, "alice@example.com"),
+        ];
+
+        let at = |day: u32, hour: u32| NaiveDate::from_ymd_opt(2023, 1, day).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+
+        assert_eq!(find_slot(&schedule, "alice@example.com", at(4, 12)), Some(2));
+        assert_eq!(find_slot(&schedule, "bob@example.com", at(3, 0)), Some(1), "a date should select the human's slot on that day");
+        assert_eq!(find_slot(&schedule, "alice@example.com", at(3, 0)), None, "alice is not on-call on the 3rd");
+    }
+
+    #[test]
+    fn test_find_swaps_rest() {
+        let config = Config::new(Duration::hours(8))
+            .with_human("alice@example.com", Human::default())
+            .with_human("bob@example.com", Human::default().with_constraints(vec![
+                Constraint::TimeOfDay { start: NaiveTime::from_hms_opt(16, 0, 0).unwrap(), end: NaiveTime::from_hms_opt(20, 0, 0).unwrap() },
+            ]))
+            .with_human("dave@example.com", Human::default());
+
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let slot = |from: i64, to: i64, human: &str| ScheduleSlot {
+            time: TimeRange::new(start + Duration::hours(from), start + Duration::hours(to)),
+            human: Some(human.to_string()),
+        };
+
+        let schedule = vec![
+            slot(8, 16, "alice@example.com"),
+            slot(16, 20, "bob@example.com"),
+            slot(32, 40, "dave@example.com"),
+            slot(56, 64, "alice@example.com"),
+        ];
+
+        let swaps = find_swaps(&config, &schedule, 1);
+        assert!(
+            swaps.iter().all(|swap| swap.human != "alice@example.com"),
+            "bob can't take any of alice's slots in return and taking theirs would leave alice on-call for 12 hours without a break"
+        );
+
+        let swaps = find_swaps(&config, &schedule, 2);
+        assert!(
+            swaps.iter().any(|swap| swap.human == "alice@example.com"),
+            "alice's business hours on consecutive days are separated by a break, so they may take dave's slot"
+        );
+    }
+}
@@ -0,0 +1,387 @@
+use std::{fmt::Display, path::Path};
+
+use chrono::NaiveDateTime;
+
+use crate::{config::Config, constraints::Constraint, solver::ScheduleSlot};
+
+/// A single element of the path to a field within the configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// How serious a problem is. Errors make the configuration unusable, while warnings (like a human
+/// who is on leave for the whole schedule) are worth knowing about but don't prevent scheduling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem with the configuration, identified by the path to the field which caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub path: Vec<Segment>,
+    pub message: String,
+    pub severity: Severity,
+}
+
+/// A (1-based) line and column within the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Problem {
+    fn new<S: Into<String>>(path: Vec<Segment>, message: S) -> Self {
+        Self {
+            path,
+            message: message.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    fn warning<S: Into<String>>(path: Vec<Segment>, message: S) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(path, message)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Describes this problem as found in the configuration file at `path`, including the line and
+    /// column at which it occurs within the file's `source` (when it can be found).
+    pub fn describe(&self, path: &Path, source: &str) -> String {
+        match self.location(source) {
+            Some(location) => format!("{}:{}: {}: {}", path.display(), location, self.severity, self),
+            None => format!("{}: {}: {}", path.display(), self.severity, self),
+        }
+    }
+
+    /// Attempts to find the location of the field which caused this problem within the YAML `source`.
+    pub fn location(&self, source: &str) -> Option<Location> {
+        locate(source, &self.path)
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+macro_rules! path {
+    ($($segment:expr),*) => {
+        vec![$(Segment::from($segment)),*]
+    };
+}
+
+impl From<&str> for Segment {
+    fn from(key: &str) -> Self {
+        Segment::Key(key.to_string())
+    }
+}
+
+impl From<&String> for Segment {
+    fn from(key: &String) -> Self {
+        Segment::Key(key.clone())
+    }
+}
+
+impl From<usize> for Segment {
+    fn from(index: usize) -> Self {
+        Segment::Index(index)
+    }
+}
+
+/// Checks the configuration for problems which would prevent a reasonable schedule from being
+/// generated between `start` and `end`, returning all of the problems which were found. Problems
+/// with the configuration itself are errors, while gaps in coverage during this period are warnings.
+pub fn validate(config: &Config, start: NaiveDateTime, end: NaiveDateTime) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    if config.shift_length <= chrono::Duration::zero() {
+        problems.push(Problem::new(path!["shiftLength"], "the shift length must be greater than zero"));
+    }
+
+    if config.humans.is_empty() {
+        problems.push(Problem::new(path!["humans"], "at least one human must be configured"));
+    }
+
+    for (index, constraint) in config.constraints.iter().enumerate() {
+        validate_constraint(constraint, path!["constraints", index], &mut problems);
+    }
+
+    let mut humans: Vec<_> = config.humans.iter().collect();
+    humans.sort_by_key(|(name, _)| *name);
+
+    for (name, human) in humans.iter() {
+        if !human.capacity.is_finite() || human.capacity <= 0.0 {
+            problems.push(Problem::new(path!["humans", *name, "capacity"], "the capacity must be a number greater than zero"));
+        }
+
+        for (index, constraint) in human.constraints.iter().enumerate() {
+            validate_constraint(constraint, path!["humans", *name, "constraints", index], &mut problems);
+        }
+    }
+
+    if start >= end {
+        return problems;
+    }
+
+    let slots = config.slots(start, end);
+    if slots.is_empty() {
+        problems.push(Problem::warning(path!["constraints"], format!("the constraints do not require any on-call coverage between {} and {}", start, end)));
+        return problems;
+    }
+
+    let mut covered = vec![false; slots.len()];
+    for (name, human) in humans.iter() {
+        let coverage = human.possible_coverage(&slots);
+        if !coverage.iter().any(|c| *c) {
+            problems.push(Problem::warning(path!["humans", *name], format!("the constraints for this human prevent them from covering any shifts between {} and {}", start, end)));
+        }
+
+        for (covered, available) in covered.iter_mut().zip(coverage) {
+            *covered |= available;
+        }
+    }
+
+    let uncovered: Vec<_> = slots.iter().zip(covered).filter(|(_, covered)| !covered).map(|(slot, _)| slot).collect();
+    if !config.humans.is_empty() && !uncovered.is_empty() {
+        problems.push(Problem::warning(path!["humans"], format!("{} slot(s) cannot be covered by any human, starting with {}", uncovered.len(), uncovered[0])));
+    }
+
+    problems
+}
+
+fn validate_constraint(constraint: &Constraint, path: Vec<Segment>, problems: &mut Vec<Problem>) {
+    match constraint {
+        Constraint::DayOfWeek(days) if days.is_empty() => {
+            problems.push(Problem::new(path, "a DayOfWeek constraint must include at least one day"));
+        },
+        Constraint::TimeOfDay { start, end } if start == end => {
+            problems.push(Problem::new(path, format!("a TimeOfDay constraint must have different start and end times (both are {})", start)));
+        },
+        Constraint::Unavailable { start, end } if end < start => {
+            problems.push(Problem::new(path, format!("an Unavailable constraint must not end ({}) before it starts ({})", end, start)));
+        },
+        Constraint::Unavailable { start, end } if end == start => {
+            problems.push(Problem::new(path, format!("an Unavailable constraint must end after it starts (both are {})", start)));
+        },
+        _ => {},
+    }
+}
+
+/// Checks a schedule (which may have been edited by hand) for problems which could affect its summary,
+/// such as overlapping slots or humans who are not part of the configuration.
+pub fn check_schedule(config: Option<&Config>, schedule: &[ScheduleSlot]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let mut slots: Vec<&ScheduleSlot> = schedule.iter().collect();
+    slots.sort_by_key(|slot| slot.time);
+
+    for pair in slots.windows(2) {
+        if pair[1].time.start < pair[0].time.end {
+            problems.push(format!("the slots {} and {} overlap", pair[0].time, pair[1].time));
+        }
+    }
+
+    for slot in slots.iter().filter(|slot| slot.time.end <= slot.time.start) {
+        problems.push(format!("the slot {} does not cover any time", slot.time));
+    }
+
+    if let Some(config) = config {
+        let mut unknown: Vec<&str> = schedule.iter()
+            .filter_map(|slot| slot.human.as_deref())
+            .filter(|human| !config.humans.contains_key(*human))
+            .collect();
+        unknown.sort();
+        unknown.dedup();
+
+        for human in unknown {
+            problems.push(format!("{} is not one of the humans in the configuration", human));
+        }
+    }
+
+    problems
+}
+
+/// Finds the line and column at which the field identified by `path` is declared within the YAML
+/// `source`. This is a best-effort search which understands block-style mappings and sequences,
+/// falling back to the closest parent it was able to find when it encounters anything else.
+pub(crate) fn locate(source: &str, path: &[Segment]) -> Option<Location> {
+    let lines: Vec<&str> = source.lines().collect();
+
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let is_content = |line: &str| {
+        let trimmed = line.trim();
+        !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "---"
+    };
+
+    let mut range = 0..lines.len();
+    let mut parent_indent: Option<usize> = None;
+    let mut location = None;
+
+    for segment in path {
+        let children: Vec<usize> = range.clone()
+            .filter(|&i| is_content(lines[i]))
+            .filter(|&i| parent_indent.map(|p| indent(lines[i]) >= p).unwrap_or(true))
+            .collect();
+
+        let child_indent = match children.iter().map(|&i| indent(lines[i])).min() {
+            Some(child_indent) => child_indent,
+            None => return location,
+        };
+        let children: Vec<usize> = children.into_iter().filter(|&i| indent(lines[i]) == child_indent).collect();
+
+        let found = match segment {
+            Segment::Key(key) => children.into_iter().find(|&i| {
+                let content = lines[i].trim_start();
+                let content = content.strip_prefix("- ").map(|c| c.trim_start()).unwrap_or(content);
+                [key.to_string(), format!("\"{}\"", key), format!("'{}'", key)]
+                    .iter()
+                    .any(|k| content.strip_prefix(k.as_str()).map(|rest| rest.trim_start().starts_with(':')).unwrap_or(false))
+            }),
+            Segment::Index(index) => children.into_iter()
+                .filter(|&i| lines[i].trim_start().starts_with('-'))
+                .nth(*index),
+        };
+
+        let found = match found {
+            Some(found) => found,
+            None => return location,
+        };
+
+        location = Some(Location {
+            line: found + 1,
+            column: indent(lines[found]) + 1,
+        });
+
+        let found_indent = indent(lines[found]);
+        let end = (found + 1..range.end)
+            .find(|&i| is_content(lines[i]) && (indent(lines[i]) < found_indent || (indent(lines[i]) == found_indent && !(matches!(segment, Segment::Key(_)) && lines[i].trim_start().starts_with('-')))))
+            .unwrap_or(range.end);
+
+        range = found + 1..end;
+        parent_indent = Some(found_indent + if matches!(segment, Segment::Index(_)) { 1 } else { 0 });
+    }
+
+    location
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+shiftLength: 1
+constraints:
+  - !DayOfWeek [Mon, Tue, Wed, Thu, Fri]
+  - !TimeOfDay
+    start: 08:00:00
+    end: 08:00:00
+humans:
+  alice@example.com:
+    constraints:
+    - !DayOfWeek []
+  bob@example.com:
+    capacity: 0
+    constraints:
+      - !None
+      - !Unavailable
+        start: 2023-01-07
+        end: 2023-01-01
+"#;
+
+    #[test]
+    fn test_validate() {
+        let config: Config = serde_yaml::from_str(CONFIG).unwrap();
+        let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+        let problems = validate(&config, start, start + Duration::days(28));
+        assert_eq!(problems.iter().filter(|p| !p.is_error()).count(), 1, "only the coverage problem should be a warning");
+        let problems: Vec<_> = problems.iter().map(|p| (p.to_string(), p.location(CONFIG))).collect();
+
+        assert_eq!(problems, vec![
+            ("constraints[1]: a TimeOfDay constraint must have different start and end times (both are 08:00:00)".to_string(), Some(Location { line: 5, column: 3 })),
+            ("humans.alice@example.com.constraints[0]: a DayOfWeek constraint must include at least one day".to_string(), Some(Location { line: 11, column: 5 })),
+            ("humans.bob@example.com.capacity: the capacity must be a number greater than zero".to_string(), Some(Location { line: 13, column: 5 })),
+            ("humans.bob@example.com.constraints[1]: an Unavailable constraint must not end (2023-01-01) before it starts (2023-01-07)".to_string(), Some(Location { line: 16, column: 7 })),
+            ("humans.alice@example.com: the constraints for this human prevent them from covering any shifts between 2023-01-01 00:00:00 and 2023-01-29 00:00:00".to_string(), Some(Location { line: 9, column: 3 })),
+        ]);
+    }
+
+    #[test]
+    fn test_validate_coverage() {
+        let config: Config = serde_yaml::from_str(r#"
+shiftLength: 1
+constraints:
+  - !DayOfWeek [Mon, Tue]
+  - !TimeOfDay
+    start: 09:00:00
+    end: 17:00:00
+humans:
+  alice@example.com:
+    constraints:
+      - !DayOfWeek [Mon]
+  bob@example.com:
+    constraints:
+      - !DayOfWeek [Sat]
+"#).unwrap();
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+        let problems: Vec<_> = validate(&config, start, start + Duration::days(7)).iter().map(|p| p.to_string()).collect();
+
+        assert_eq!(problems, vec![
+            "humans.bob@example.com: the constraints for this human prevent them from covering any shifts between 2023-01-02 00:00:00 and 2023-01-09 00:00:00".to_string(),
+            "humans: 1 slot(s) cannot be covered by any human, starting with 2023-01-03 09:00:00 - 2023-01-03 17:00:00".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_check_schedule() {
+        let config = Config::new(Duration::days(1)).with_human("alice@example.com", crate::config::Human::default());
+        let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let slot = |offset: i64, human: &str| ScheduleSlot {
+            time: crate::timerange::TimeRange::new(start + Duration::hours(offset), start + Duration::hours(offset + 8)),
+            human: Some(human.into()),
+        };
+
+        let problems = check_schedule(Some(&config), &[slot(0, "alice@example.com"), slot(4, "bob@example.com")]);
+        assert_eq!(problems, vec![
+            "the slots 2023-01-02 00:00:00 - 2023-01-02 08:00:00 and 2023-01-02 04:00:00 - 2023-01-02 12:00:00 overlap".to_string(),
+            "bob@example.com is not one of the humans in the configuration".to_string(),
+        ]);
+    }
+}